@@ -0,0 +1,4 @@
+ALTER TABLE IF EXISTS chapters
+ADD COLUMN IF NOT EXISTS "opens_at" TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS "locks_at" TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS "reveal_at" TIMESTAMPTZ;
@@ -0,0 +1,6 @@
+-- Events were never closed individually, so every existing event starts open
+UPDATE events
+SET is_open = TRUE;
+
+ALTER TABLE IF EXISTS events
+ALTER COLUMN is_open SET DEFAULT TRUE;
@@ -0,0 +1 @@
+ALTER TYPE event_types ADD VALUE IF NOT EXISTS 'over_under';
@@ -0,0 +1,10 @@
+ALTER TABLE IF EXISTS chapters
+ADD COLUMN IF NOT EXISTS tiebreaker TEXT,
+ADD COLUMN IF NOT EXISTS tiebreaker_answer DOUBLE PRECISION;
+
+CREATE TABLE IF NOT EXISTS tiebreakers (
+	"chapter_id"	INTEGER NOT NULL REFERENCES chapters(id) ON DELETE CASCADE,
+	"user_id"		INTEGER NOT NULL REFERENCES users(id),
+	"guess"			DOUBLE PRECISION NOT NULL,
+	PRIMARY KEY (chapter_id, user_id)
+);
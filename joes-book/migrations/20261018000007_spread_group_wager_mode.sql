@@ -0,0 +1,9 @@
+UPDATE events
+SET contents = jsonb_build_object(
+	'spread_group',
+	jsonb_build_object(
+		'spreads', contents -> 'spread_group',
+		'wager_mode', jsonb_build_object('mode', 'confidence')
+	)
+)
+WHERE event_type = 'spread_group' AND jsonb_typeof(contents -> 'spread_group') = 'array';
@@ -0,0 +1,13 @@
+DO $$ BEGIN
+	CREATE TYPE book_types AS ENUM ('standard', 'survivor');
+EXCEPTION
+	WHEN duplicate_object THEN NULL;
+END $$;
+
+ALTER TABLE IF EXISTS books
+ADD COLUMN IF NOT EXISTS kind book_types NOT NULL DEFAULT 'standard';
+
+ALTER TABLE IF EXISTS subscriptions
+ADD COLUMN IF NOT EXISTS eliminated_in INTEGER REFERENCES chapters(id) ON DELETE SET NULL;
+
+ALTER TYPE event_types ADD VALUE IF NOT EXISTS 'survivor';
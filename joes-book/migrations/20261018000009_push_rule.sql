@@ -0,0 +1,8 @@
+DO $$ BEGIN
+	CREATE TYPE push_rules AS ENUM ('zero', 'refund', 'half');
+EXCEPTION
+	WHEN duplicate_object THEN NULL;
+END $$;
+
+ALTER TABLE IF EXISTS books
+ADD COLUMN IF NOT EXISTS push_rule push_rules NOT NULL DEFAULT 'zero';
@@ -0,0 +1,4 @@
+-- Spread group picks cover several games, so correctness is stored the same
+-- way as choice and wager: a scalar for most events, an array for spread groups
+ALTER TABLE IF EXISTS picks
+ALTER COLUMN correct TYPE JSONB USING TO_JSONB(correct);
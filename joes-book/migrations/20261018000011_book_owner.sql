@@ -0,0 +1,17 @@
+-- Books created before owners existed hand ownership to their longest standing admin
+UPDATE subscriptions AS s
+SET role = '"owner"'::JSONB
+FROM (
+	SELECT DISTINCT ON (book_id) book_id, user_id
+	FROM subscriptions
+	WHERE role = '"admin"'::JSONB
+	ORDER BY book_id, user_id
+) AS first_admin
+WHERE
+	s.book_id = first_admin.book_id
+	AND s.user_id = first_admin.user_id
+	AND NOT EXISTS (
+		SELECT 1
+		FROM subscriptions AS owners
+		WHERE owners.book_id = s.book_id AND owners.role = '"owner"'::JSONB
+	);
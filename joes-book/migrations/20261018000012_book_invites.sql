@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS book_invites (
+    token text NOT NULL DEFAULT md5((gen_random_uuid())::text),
+    book_id integer NOT NULL REFERENCES books(id) ON DELETE CASCADE,
+    role jsonb NOT NULL,
+    expires_at timestamp with time zone,
+    max_uses integer,
+    uses integer NOT NULL DEFAULT 0,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (token)
+);
@@ -0,0 +1,9 @@
+ALTER TABLE IF EXISTS books
+ADD COLUMN IF NOT EXISTS accepts_join_requests BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS join_requests (
+    book_id integer NOT NULL REFERENCES books(id) ON DELETE CASCADE,
+    user_id integer NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (book_id, user_id)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE IF EXISTS books
+ADD COLUMN IF NOT EXISTS description TEXT,
+ADD COLUMN IF NOT EXISTS rules TEXT,
+ADD COLUMN IF NOT EXISTS season TEXT,
+ADD COLUMN IF NOT EXISTS archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS chapter_templates (
+    id serial PRIMARY KEY,
+    book_id integer NOT NULL REFERENCES books(id) ON DELETE CASCADE,
+    name text NOT NULL,
+    events jsonb NOT NULL DEFAULT '[]',
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
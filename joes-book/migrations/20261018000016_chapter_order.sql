@@ -0,0 +1,13 @@
+ALTER TABLE IF EXISTS chapters
+ADD COLUMN IF NOT EXISTS sort_order INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS week INTEGER,
+ADD COLUMN IF NOT EXISTS section TEXT;
+
+-- Keep the existing newest first order
+UPDATE chapters
+SET sort_order = ordered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY book_id ORDER BY created_at) AS position
+    FROM chapters
+) AS ordered
+WHERE chapters.id = ordered.id AND chapters.sort_order = 0;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS pick_history (
+    id serial PRIMARY KEY,
+    book_id integer NOT NULL REFERENCES books(id) ON DELETE CASCADE,
+    chapter_id integer NOT NULL REFERENCES chapters(id) ON DELETE CASCADE,
+    event_id integer REFERENCES events(id) ON DELETE SET NULL,
+    user_id integer NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    choice jsonb NOT NULL,
+    wager jsonb NOT NULL,
+    submitted_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS pick_history_chapter_idx ON pick_history (chapter_id, user_id);
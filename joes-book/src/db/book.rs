@@ -13,6 +13,36 @@ pub enum BookRole {
     Unauthorized,
 }
 
+impl BookRole {
+    /// Owners have every admin permission on top of their own
+    pub fn is_admin(&self) -> bool {
+        matches!(self, BookRole::Owner | BookRole::Admin)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "book_types", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BookType {
+    #[default]
+    Standard,
+    Survivor,
+}
+
+/// How a pick on a game that lands exactly on the line is scored
+#[derive(Debug, Clone, Copy, Default, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "push_rules", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PushRule {
+    #[default]
+    Zero,
+    Refund,
+    Half,
+}
+
+/// Longest book name, in characters, accepted on create or rename
+pub const MAX_BOOK_NAME_LEN: usize = 64;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BookSubscription {
     pub id: i32,
@@ -20,11 +50,14 @@ pub struct BookSubscription {
     pub name: String,
     #[sqlx(json)]
     pub role: BookRole,
+    pub kind: BookType,
+    pub eliminated_in: Option<i32>,
+    pub archived: bool,
 }
 
 pub async fn get_books(user_id: i32, pool: &PgPool) -> Result<Vec<BookSubscription>, AppError> {
     let result = sqlx::query_as::<_, BookSubscription>(
-        r#"	SELECT b.id AS id, b.name, s.role, s.user_id
+        r#"	SELECT b.id AS id, b.name, s.role, s.user_id, b.kind, s.eliminated_in, b.archived
 			FROM books AS b
 			INNER JOIN subscriptions AS s ON s.book_id=b.id
 			WHERE s.user_id = $1
@@ -44,7 +77,7 @@ pub async fn get_book(
 ) -> Result<BookSubscription, sqlx::Error> {
     sqlx::query_as::<_, BookSubscription>(
         r#"
-            SELECT b.id AS id, b.name, s.role, s.user_id
+            SELECT b.id AS id, b.name, s.role, s.user_id, b.kind, s.eliminated_in, b.archived
             FROM books AS b
             INNER JOIN subscriptions AS s ON s.book_id=b.id
             WHERE s.user_id = $1 AND b.id = $2
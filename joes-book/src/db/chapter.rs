@@ -1,4 +1,5 @@
 use sqlx::PgPool;
+use time::OffsetDateTime;
 
 use crate::AppError;
 
@@ -9,15 +10,27 @@ pub struct Chapter {
     pub is_open: bool,
     pub is_visible: bool,
     pub title: String,
+    pub opens_at: Option<OffsetDateTime>,
+    pub locks_at: Option<OffsetDateTime>,
+    pub reveal_at: Option<OffsetDateTime>,
+    pub tiebreaker: Option<String>,
+    pub tiebreaker_answer: Option<f64>,
+    /// Markdown written by admins, shown above the chapter's events
+    pub notes: Option<String>,
+    /// Chapters are listed from the highest sort order down
+    pub sort_order: i32,
+    pub week: Option<i32>,
+    /// Optional heading the chapter is grouped under, such as "Playoffs"
+    pub section: Option<String>,
 }
 
 pub async fn get_chapters(book_id: i32, pool: &PgPool) -> Result<Vec<Chapter>, sqlx::Error> {
     sqlx::query_as!(
         Chapter,
-        r#"	SELECT id AS chapter_id, book_id, is_open, title, is_visible
+        r#"	SELECT id AS chapter_id, book_id, is_open, title, is_visible, opens_at, locks_at, reveal_at, tiebreaker, tiebreaker_answer, notes, sort_order, week, section
 			FROM chapters
 			WHERE book_id = $1
-            ORDER BY created_at DESC
+            ORDER BY sort_order DESC, created_at DESC
 		"#,
         book_id
     )
@@ -28,7 +41,7 @@ pub async fn get_chapters(book_id: i32, pool: &PgPool) -> Result<Vec<Chapter>, s
 pub async fn get_chapter(chapter_id: i32, pool: &PgPool) -> Result<Chapter, sqlx::Error> {
     sqlx::query_as!(
         Chapter,
-        r#"	SELECT id AS chapter_id, book_id, title, is_open, is_visible
+        r#"	SELECT id AS chapter_id, book_id, title, is_open, is_visible, opens_at, locks_at, reveal_at, tiebreaker, tiebreaker_answer, notes, sort_order, week, section
 			FROM chapters
 			WHERE id = $1
 		"#,
@@ -38,10 +51,49 @@ pub async fn get_chapter(chapter_id: i32, pool: &PgPool) -> Result<Chapter, sqlx
     .await
 }
 
+/// Applies any scheduled transitions that have come due, clearing each schedule once applied
+/// so that manual toggles are not overridden afterwards
+pub async fn apply_schedules(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE chapters
+        SET is_visible = TRUE, reveal_at = NULL
+        WHERE reveal_at <= NOW()
+        "
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE chapters
+        SET is_open = TRUE, opens_at = NULL
+        WHERE opens_at <= NOW()
+        "
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE chapters
+        SET is_open = FALSE, locks_at = NULL
+        WHERE locks_at <= NOW()
+        "
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await
+}
+
 pub struct ChapterUser {
     pub user_id: i32,
     pub username: String,
     pub total_points: i32,
+    pub tiebreaker: Option<f64>,
 }
 
 pub async fn get_chapter_users(
@@ -52,7 +104,7 @@ pub async fn get_chapter_users(
     sqlx::query_as!(
         ChapterUser,
         r#"
-        SELECT user_id, username, COALESCE(total_points, 0)::INT as "total_points!"
+        SELECT sub3.user_id, username, COALESCE(total_points, 0)::INT as "total_points!", tiebreakers.guess AS "tiebreaker?"
         FROM (
             SELECT
                 sub1.id AS user_id,
@@ -73,7 +125,9 @@ pub async fn get_chapter_users(
                 sub1.ID,
                 sub1.USERNAME
         ) AS sub3
-        ORDER BY total_points DESC, username
+        LEFT JOIN tiebreakers ON tiebreakers.chapter_id = $2 AND tiebreakers.user_id = sub3.user_id
+        LEFT JOIN chapters ON chapters.id = $2
+        ORDER BY total_points DESC, ABS(tiebreakers.guess - chapters.tiebreaker_answer) NULLS LAST, username
         "#,
         book_id,
         chapter_id
@@ -82,3 +136,21 @@ pub async fn get_chapter_users(
     .await
     .map_err(AppError::from)
 }
+
+pub async fn get_tiebreaker(
+    user_id: i32,
+    chapter_id: i32,
+    pool: &PgPool,
+) -> Result<Option<f64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "
+        SELECT guess
+        FROM tiebreakers
+        WHERE user_id = $1 AND chapter_id = $2
+        ",
+        user_id,
+        chapter_id
+    )
+    .fetch_optional(pool)
+    .await
+}
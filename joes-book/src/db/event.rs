@@ -2,18 +2,26 @@ use std::collections::HashMap;
 
 use crate::AppError;
 
-use super::{spread::Spread, user_input::UserInput};
+use super::{
+    moneyline::Moneyline, multiple_choice::MultipleChoice, numeric::Numeric, over_under::OverUnder,
+    spread::SpreadGroup, survivor::Survivor, user_input::UserInput,
+};
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sqlx::types::Json;
 use sqlx::PgPool;
 
-#[derive(Debug, Clone, sqlx::Type, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "event_types", rename_all = "snake_case")]
 pub enum EventType {
     SpreadGroup,
     UserInput,
+    Moneyline,
+    OverUnder,
+    MultipleChoice,
+    Numeric,
+    Survivor,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -29,8 +37,56 @@ pub struct Event {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EventContent {
-    SpreadGroup(Vec<Spread>),
+    SpreadGroup(SpreadGroup),
     UserInput(UserInput),
+    Moneyline(Moneyline),
+    OverUnder(OverUnder),
+    MultipleChoice(MultipleChoice),
+    Numeric(Numeric),
+    Survivor(Survivor),
+}
+
+impl EventContent {
+    /// The same event with every answer removed, ready to be picked again
+    pub fn without_answers(mut self) -> Self {
+        match &mut self {
+            EventContent::SpreadGroup(group) => group
+                .spreads
+                .iter_mut()
+                .for_each(|spread| spread.answer = None),
+            EventContent::UserInput(input) => input.acceptable_answers = None,
+            EventContent::Moneyline(moneyline) => moneyline.answer = None,
+            EventContent::OverUnder(over_under) => over_under.answer = None,
+            EventContent::MultipleChoice(multiple_choice) => multiple_choice.answer = None,
+            EventContent::Numeric(numeric) => numeric.answer = None,
+            EventContent::Survivor(survivor) => survivor
+                .games
+                .iter_mut()
+                .for_each(|game| game.winner = None),
+        }
+        self
+    }
+
+    /// Every team playing in this event
+    pub fn team_ids(&self) -> Vec<i32> {
+        match self {
+            EventContent::SpreadGroup(group) => group
+                .spreads
+                .iter()
+                .flat_map(|spread| [spread.home_id, spread.away_id])
+                .collect(),
+            EventContent::Moneyline(moneyline) => vec![moneyline.home_id, moneyline.away_id],
+            EventContent::OverUnder(over_under) => vec![over_under.home_id, over_under.away_id],
+            EventContent::Survivor(survivor) => survivor
+                .games
+                .iter()
+                .flat_map(|game| [game.home_id, game.away_id])
+                .collect(),
+            EventContent::UserInput(_)
+            | EventContent::MultipleChoice(_)
+            | EventContent::Numeric(_) => Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -152,11 +208,43 @@ pub enum ChapterPick {
         choice: Vec<String>,
         wager: Vec<i32>,
         points: Option<i32>,
+        correct: Option<Vec<Option<bool>>>,
     },
     UserInput {
         choice: String,
         wager: i32,
         points: Option<i32>,
+        correct: Option<bool>,
+    },
+    Moneyline {
+        choice: String,
+        wager: i32,
+        points: Option<i32>,
+        correct: Option<bool>,
+    },
+    OverUnder {
+        choice: String,
+        wager: i32,
+        points: Option<i32>,
+        correct: Option<bool>,
+    },
+    MultipleChoice {
+        choice: String,
+        wager: i32,
+        points: Option<i32>,
+        correct: Option<bool>,
+    },
+    Numeric {
+        choice: f64,
+        wager: i32,
+        points: Option<i32>,
+        correct: Option<bool>,
+    },
+    Survivor {
+        choice: i32,
+        wager: i32,
+        points: Option<i32>,
+        correct: Option<bool>,
     },
 }
 
@@ -177,7 +265,9 @@ pub async fn get_chapter_picks(
                     'wager',
                     PICKS.WAGER,
                     'points',
-                    PICKS.POINTS
+                    PICKS.POINTS,
+                    'correct',
+                    PICKS.CORRECT
                 ) AS contents
             FROM
                 EVENTS
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool, Postgres, Transaction};
+use time::OffsetDateTime;
+
+use super::book::BookRole;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invite {
+    pub token: String,
+    pub book_id: i32,
+    pub role: Json<BookRole>,
+    pub expires_at: Option<OffsetDateTime>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+}
+
+/// Name of the cookie that holds an invite token while a visitor logs in or signs up
+pub const INVITE_COOKIE: &str = "invite_token";
+
+pub async fn get_invites(book_id: i32, pool: &PgPool) -> Result<Vec<Invite>, sqlx::Error> {
+    sqlx::query_as!(
+        Invite,
+        r#"
+        SELECT token, book_id, role AS "role: Json<BookRole>", expires_at, max_uses, uses
+        FROM book_invites
+        WHERE book_id = $1
+        ORDER BY created_at
+        "#,
+        book_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Joins `user_id` to the book behind `token`, returning the book's id. Expired or used up
+/// invites, and invites to archived books, return `None`. Existing members are sent to the
+/// book without using up the invite
+pub async fn redeem_invite(
+    token: &str,
+    user_id: i32,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<Option<i32>, sqlx::Error> {
+    let Some(invite) = sqlx::query!(
+        r#"
+        SELECT book_id, role AS "role: Json<BookRole>"
+        FROM book_invites
+        WHERE
+            token = $1
+            AND (expires_at IS NULL OR expires_at > NOW())
+            AND (max_uses IS NULL OR uses < max_uses)
+            AND book_id IN (SELECT id FROM books WHERE NOT archived)
+        FOR UPDATE
+        "#,
+        token
+    )
+    .fetch_optional(&mut **transaction)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let joined = sqlx::query!(
+        "
+        INSERT INTO subscriptions (user_id, book_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, book_id)
+        DO NOTHING
+        RETURNING user_id
+        ",
+        user_id,
+        invite.book_id,
+        invite.role as _
+    )
+    .fetch_optional(&mut **transaction)
+    .await?;
+
+    if joined.is_some() {
+        sqlx::query!(
+            "
+            UPDATE book_invites
+            SET uses = uses + 1
+            WHERE token = $1
+            ",
+            token
+        )
+        .execute(&mut **transaction)
+        .await?;
+    }
+
+    Ok(Some(invite.book_id))
+}
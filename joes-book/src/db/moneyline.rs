@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Moneyline {
+    pub home_id: i32,
+    pub away_id: i32,
+    pub points: i32,
+    pub answer: Option<String>,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipleChoice {
+    pub title: String,
+    pub description: Option<String>,
+    pub points: i32,
+    pub options: Vec<String>,
+    pub answer: Option<String>,
+}
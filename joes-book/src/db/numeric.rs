@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Numeric {
+    pub title: String,
+    pub description: Option<String>,
+    pub points: i32,
+    pub scoring: NumericScoring,
+    pub answer: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericScoring {
+    /// The guess or guesses nearest the answer earn all of the points
+    Closest,
+    /// Every guess loses a point for each unit it is away from the answer
+    Distance,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverUnder {
+    pub home_id: i32,
+    pub away_id: i32,
+    pub total: f64,
+    pub points: i32,
+    pub answer: Option<String>,
+}
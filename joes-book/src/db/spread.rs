@@ -1,10 +1,40 @@
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Spread {
     pub home_id: i32,
     pub away_id: i32,
     pub home_spread: f64,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub start_time: Option<OffsetDateTime>,
     pub answer: Option<String>,
     pub notes: Option<String>,
 }
+
+impl Spread {
+    /// A game is locked for picking once its kickoff has passed
+    pub fn has_started(&self, now: OffsetDateTime) -> bool {
+        self.start_time.is_some_and(|start_time| start_time <= now)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadGroup {
+    pub spreads: Vec<Spread>,
+    #[serde(default)]
+    pub wager_mode: WagerMode,
+}
+
+/// How players may distribute points across the games in a spread group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum WagerMode {
+    /// Each game gets a unique wager from 1 to the number of games
+    #[default]
+    Confidence,
+    /// Every game is worth a single point
+    Flat,
+    /// A fixed number of points split freely across the games
+    Bankroll { total: i32 },
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Survivor {
+    pub games: Vec<SurvivorGame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurvivorGame {
+    pub home_id: i32,
+    pub away_id: i32,
+    pub winner: Option<String>,
+}
+
+impl Survivor {
+    pub fn game_for(&self, team_id: i32) -> Option<&SurvivorGame> {
+        self.games
+            .iter()
+            .find(|game| game.home_id == team_id || game.away_id == team_id)
+    }
+
+    /// Whether a player who picked `team_id` survives, or `None` until that game is graded.
+    /// Ties (pushes) are survived
+    pub fn survived(&self, team_id: i32) -> Option<bool> {
+        let game = self.game_for(team_id)?;
+        match game.winner.as_deref()? {
+            "home" => Some(game.home_id == team_id),
+            "away" => Some(game.away_id == team_id),
+            "push" => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn is_graded(&self) -> bool {
+        self.games.iter().all(|game| {
+            game.winner
+                .as_deref()
+                .is_some_and(|winner| winner != "unpicked")
+        })
+    }
+}
+
+/// Teams a user has picked across a survivor book, mapped to the id and title of the chapter they were used in
+pub async fn get_used_teams(
+    book_id: i32,
+    user_id: i32,
+    pool: &PgPool,
+) -> Result<HashMap<i32, (i32, String)>, sqlx::Error> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            (PICKS.CHOICE #>> '{}')::INT AS "team_id!",
+            CHAPTERS.ID AS CHAPTER_ID,
+            CHAPTERS.TITLE
+        FROM
+            PICKS
+            JOIN EVENTS ON PICKS.EVENT_ID = EVENTS.ID
+            JOIN CHAPTERS ON PICKS.CHAPTER_ID = CHAPTERS.ID
+        WHERE
+            PICKS.BOOK_ID = $1
+            AND PICKS.USER_ID = $2
+            AND EVENTS.EVENT_TYPE = 'survivor'
+        "#,
+        book_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.team_id, (row.chapter_id, row.title)))
+    .collect())
+}
+
+/// Recomputes who was knocked out by a chapter's survivor event. Players are eliminated when
+/// their team loses, or when every game has been graded and they never made a pick. Missing a
+/// pick only counts against participants, admins and guests invited to the chapter
+pub async fn apply_eliminations(
+    book_id: i32,
+    chapter_id: i32,
+    event_id: i32,
+    survivor: &Survivor,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "
+        UPDATE subscriptions
+        SET eliminated_in = NULL
+        WHERE book_id = $1 AND eliminated_in = $2
+        ",
+        book_id,
+        chapter_id
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    let picks = sqlx::query!(
+        r#"
+        SELECT user_id, (choice #>> '{}')::INT AS "team_id!"
+        FROM picks
+        WHERE event_id = $1
+        "#,
+        event_id
+    )
+    .fetch_all(&mut **transaction)
+    .await?
+    .into_iter()
+    .map(|row| (row.user_id, row.team_id))
+    .collect::<HashMap<_, _>>();
+
+    let mut eliminated = picks
+        .iter()
+        .filter(|(_, team_id)| survivor.survived(**team_id) == Some(false))
+        .map(|(user_id, _)| *user_id)
+        .collect::<HashSet<_>>();
+
+    if survivor.is_graded() {
+        let members = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM subscriptions
+            WHERE
+                book_id = $1
+                AND (
+                    role IN ('"participant"', '"admin"', '"owner"')
+                    OR (role -> 'guest' -> 'chapter_ids') @> TO_JSONB($2::INT)
+                )
+            "#,
+            book_id,
+            chapter_id
+        )
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        eliminated.extend(
+            members
+                .into_iter()
+                .filter(|user_id| !picks.contains_key(user_id)),
+        );
+    }
+
+    sqlx::query!(
+        "
+        UPDATE subscriptions
+        SET eliminated_in = $1
+        WHERE book_id = $2 AND eliminated_in IS NULL AND user_id = ANY($3)
+        ",
+        chapter_id,
+        book_id,
+        &eliminated.into_iter().collect::<Vec<_>>()
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
@@ -18,19 +18,31 @@ pub async fn get_chapter_teams(
             SELECT teams.*
             FROM teams
             JOIN (
-                WITH spread_cols AS (
-                    SELECT (spread->>'home_id')::INT AS home_id, (spread->>'away_id')::INT AS away_id
+                WITH game_cols AS (
+                    SELECT (game->>'home_id')::INT AS home_id, (game->>'away_id')::INT AS away_id
                     FROM (
-                        SELECT jsonb_array_elements(contents->'spread_group') AS spread
+                        SELECT jsonb_array_elements(contents->'spread_group'->'spreads') AS game
                         FROM public.events
                         WHERE contents->'spread_group' IS NOT NULL AND chapter_id = $1
+                        UNION ALL
+                        SELECT contents->'moneyline' AS game
+                        FROM public.events
+                        WHERE contents->'moneyline' IS NOT NULL AND chapter_id = $1
+                        UNION ALL
+                        SELECT jsonb_array_elements(contents->'survivor'->'games') AS game
+                        FROM public.events
+                        WHERE contents->'survivor' IS NOT NULL AND chapter_id = $1
+                        UNION ALL
+                        SELECT contents->'over_under' AS game
+                        FROM public.events
+                        WHERE contents->'over_under' IS NOT NULL AND chapter_id = $1
                     ) AS t
                 )
                 SELECT home_id AS ids
-                FROM spread_cols
+                FROM game_cols
                 UNION
                 SELECT away_id
-                FROM spread_cols
+                FROM game_cols
             ) AS cols ON cols.ids=teams.id
         "#,
         chapter_id
@@ -56,6 +68,25 @@ pub async fn get(team_id: i32, pool: &PgPool) -> Result<Option<Team>, sqlx::Erro
     .await
 }
 
+pub async fn get_teams(
+    team_ids: &[i32],
+    pool: &PgPool,
+) -> Result<HashMap<i32, (String, Option<String>)>, sqlx::Error> {
+    Ok(sqlx::query!(
+        r#"
+            SELECT *
+            FROM teams
+            WHERE id = ANY($1)
+        "#,
+        team_ids
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|record| (record.id, (record.name, record.logo)))
+    .collect::<HashMap<_, _>>())
+}
+
 pub async fn search(
     team_name: &str,
     limit: Option<i64>,
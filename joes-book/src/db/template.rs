@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool};
+
+use super::event::EventContent;
+
+/// A reusable set of events, saved from a chapter, that new chapters in the book can start from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterTemplate {
+    pub id: i32,
+    pub book_id: i32,
+    pub name: String,
+    pub events: Json<Vec<EventContent>>,
+}
+
+pub async fn get_templates(
+    book_id: i32,
+    pool: &PgPool,
+) -> Result<Vec<ChapterTemplate>, sqlx::Error> {
+    sqlx::query_as!(
+        ChapterTemplate,
+        r#"
+        SELECT id, book_id, name, events AS "events: Json<Vec<EventContent>>"
+        FROM chapter_templates
+        WHERE book_id = $1
+        ORDER BY name
+        "#,
+        book_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_template(
+    template_id: i32,
+    book_id: i32,
+    pool: &PgPool,
+) -> Result<Option<ChapterTemplate>, sqlx::Error> {
+    sqlx::query_as!(
+        ChapterTemplate,
+        r#"
+        SELECT id, book_id, name, events AS "events: Json<Vec<EventContent>>"
+        FROM chapter_templates
+        WHERE id = $1 AND book_id = $2
+        "#,
+        template_id,
+        book_id
+    )
+    .fetch_optional(pool)
+    .await
+}
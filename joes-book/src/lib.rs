@@ -7,7 +7,7 @@ use {
         handler::Handler,
         middleware,
         response::{Html, IntoResponse, Redirect},
-        routing::{get, post},
+        routing::{delete, get, post},
         Extension, Router,
     },
     axum_ctx::{RespErr, StatusCode},
@@ -15,6 +15,8 @@ use {
 };
 
 pub mod auth;
+pub mod scheduler;
+pub mod scoring;
 pub mod search;
 
 pub mod routes {
@@ -22,6 +24,7 @@ pub mod routes {
     pub mod chapter;
     pub mod finish_signup;
     pub mod home;
+    pub mod invite;
     pub mod session;
     pub mod signup;
 }
@@ -30,8 +33,15 @@ pub mod db {
     pub mod book;
     pub mod chapter;
     pub mod event;
+    pub mod invite;
+    pub mod moneyline;
+    pub mod multiple_choice;
+    pub mod numeric;
+    pub mod over_under;
     pub mod spread;
+    pub mod survivor;
     pub mod team;
+    pub mod template;
     pub mod user_input;
 }
 
@@ -136,7 +146,16 @@ pub fn router() -> Router<AppStateRef> {
                 .route("/user-input", get(chapter::admin::user_input))
                 .route("/open", post(chapter::admin::open))
                 .route("/visible", post(chapter::admin::visible))
-                .route("/unsubmitted-users", get(chapter::admin::unsubmitted_users)),
+                .route("/event-open", post(chapter::admin::event_open))
+                .route("/unsubmitted-users", get(chapter::admin::unsubmitted_users))
+                .route("/pick-history", get(chapter::admin::pick_history))
+                .route("/guest-search", get(chapter::admin::guest_search))
+                .route("/invite-guest", post(chapter::admin::invite_guest))
+                .route("/clone", post(chapter::admin::clone))
+                .route("/save-template", post(chapter::admin::save_template))
+                .route("/edit/", get(chapter::edit::get).post(chapter::edit::post))
+                .route("/edit/add", get(chapter::create::add_event))
+                .route("/edit/team-select", post(chapter::create::team_select)),
         )
         .route_layer(middleware::from_fn(book::mw::require_admin))
         .route("/{chapter_id}/", chapter_home_page)
@@ -147,18 +166,59 @@ pub fn router() -> Router<AppStateRef> {
                 .route("/", get(chapter::create::get).post(chapter::create::post))
                 .route("/add", get(chapter::create::add_event))
                 .route("/team-select", post(chapter::create::team_select))
+                .route(
+                    "/template",
+                    get(chapter::create::template).delete(chapter::create::delete_template),
+                )
                 .route_layer(middleware::from_fn(book::mw::require_admin)),
         );
 
     let book_routes = Router::new()
-        .nest("/{book_id}/chapter/", chapter_routes)
+        .nest(
+            "/{book_id}/chapter/",
+            chapter_routes.route_layer(middleware::from_fn(book::mw::reject_archived)),
+        )
         .nest(
             "/{book_id}/admin/",
             Router::new()
-                .route("/", get(book::admin::handler).delete(book::admin::delete))
+                .route("/", get(book::admin::handler))
                 .route("/user-search", get(book::admin::search_user))
                 .route("/add-user", post(book::admin::add_user))
                 .route("/remove-user", post(book::admin::remove_user))
+                .route(
+                    "/transfer",
+                    post(book::admin::transfer).layer(middleware::from_fn(book::mw::require_owner)),
+                )
+                .route("/role", post(book::admin::change_role))
+                .route(
+                    "/invites",
+                    post(book::admin::create_invite).delete(book::admin::revoke_invite),
+                )
+                .route("/push-rule", post(book::admin::push_rule))
+                .route("/chapter-order", post(book::admin::chapter_order))
+                .route("/join-requests", post(book::admin::accepts_join_requests))
+                .route(
+                    "/join-requests/approve",
+                    post(book::admin::approve_join_request),
+                )
+                .route("/join-requests/deny", post(book::admin::deny_join_request))
+                .route(
+                    "/added-points",
+                    post(book::admin::add_points).delete(book::admin::remove_points),
+                )
+                .route("/groups", delete(book::admin::delete_group))
+                .route(
+                    "/groups/members",
+                    post(book::admin::add_group_member).delete(book::admin::remove_group_member),
+                )
+                .route("/groups/rename", post(book::admin::rename_group))
+                .route_layer(middleware::from_fn(book::mw::reject_archived))
+                .route("/", delete(book::admin::delete))
+                .route(
+                    "/settings/",
+                    get(book::settings::get).post(book::settings::post),
+                )
+                .route("/settings/archive", post(book::settings::archive))
                 .route_layer(middleware::from_fn(book::mw::require_admin)),
         )
         .route("/{book_id}/leaderboard", get(book::page::leaderboard))
@@ -167,7 +227,9 @@ pub fn router() -> Router<AppStateRef> {
         .route(
             "/create",
             post(book::create::handler).layer(middleware::from_fn(authz::mw::require_site_admin)),
-        );
+        )
+        .route("/search", get(book::join::search))
+        .route("/join", post(book::join::request));
 
     let home_routes = Router::new()
         .route("/logout", post(session::logout))
@@ -213,6 +275,7 @@ pub fn router() -> Router<AppStateRef> {
         ))
         .nest_service("/public", ServeDir::new("public"))
         .merge(session_routes)
+        .route("/invite/{token}", get(invite::redeem))
         .fallback(get((StatusCode::NOT_FOUND, "Could not find your route"))) // TODO: Add funny status page
 }
 
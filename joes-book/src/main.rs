@@ -29,6 +29,8 @@ pub async fn shuttle(
         AuthManagerLayerBuilder::new(backend, session_layer).build()
     };
 
+    joes_book::scheduler::spawn(pool.clone());
+
     let state: joes_book::AppState = {
         let turnstile_site_key: String = secrets
             .get("TURNSTILE_SITE_KEY")
@@ -107,6 +109,8 @@ async fn main() {
         AuthManagerLayerBuilder::new(backend, session_layer).build()
     };
 
+    joes_book::scheduler::spawn(pool.clone());
+
     let state: joes_book::AppState = {
         let turnstile_site_key: String = std::env::var("TURNSTILE_SITE_KEY")
             .unwrap_or_else(|_| "1x00000000000000000000AA".into());
@@ -1,13 +1,21 @@
 use axum::{
     extract::{Query, State},
     response::{ErrorResponse, IntoResponse},
-    Extension, Form,
+    Extension, Form, Json,
 };
 use axum_ctx::RespErr;
+use itertools::Itertools;
 use reqwest::StatusCode;
 
 use crate::{
-    auth::AuthSession, db::book::BookSubscription, templates::authenticated, AppError, AppStateRef,
+    auth::AuthSession,
+    db::{
+        book::{BookRole, BookSubscription, PushRule},
+        chapter::{get_chapters, Chapter},
+        invite::{get_invites, Invite},
+    },
+    templates::authenticated,
+    AppError, AppNotification, AppStateRef,
 };
 
 pub async fn handler(
@@ -19,25 +27,57 @@ pub async fn handler(
     let pool = &auth_session.backend.0;
 
     let users = sqlx::query!(
-        "
-        SELECT u.id, u.username, s.role
+        r#"
+        SELECT u.id, u.username, s.role AS "role: sqlx::types::Json<BookRole>"
         FROM users AS u
         JOIN subscriptions AS s ON u.id=s.user_id
         JOIN books AS b on s.book_id=b.id
         WHERE b.id = $1 AND u.id != $2
         ORDER BY u.id
-        ",
+        "#,
         book_subscription.id,
         book_subscription.user_id
     )
     .fetch_all(pool)
     .await?;
 
+    let chapters = get_chapters(book_subscription.id, pool).await?;
+    let is_owner = book_subscription.role == BookRole::Owner;
+
+    let settings = sqlx::query!(
+        r#"SELECT push_rule AS "push_rule: PushRule", accepts_join_requests FROM books WHERE id = $1"#,
+        book_subscription.id
+    )
+    .fetch_one(pool)
+    .await?;
+    let push_rule = settings.push_rule;
+
+    let join_requests = sqlx::query!(
+        "
+        SELECT u.id, u.username, r.created_at
+        FROM join_requests AS r
+        JOIN users AS u ON r.user_id = u.id
+        WHERE r.book_id = $1
+        ORDER BY r.created_at
+        ",
+        book_subscription.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let added_points = added_points_table(book_subscription.id, pool).await?;
+    let groups = groups_table(book_subscription.id, pool).await?;
+    let invites = get_invites(book_subscription.id, pool).await?;
+
     Ok(authenticated(
         &user.username,
         Some(format!("{} - Admin", book_subscription.name).as_str()),
         None,
-        None,
+        Some(maud::html! {
+            script src="/public/js/my-enc.js" {}
+            script src="/public/js/drag-order.js" {}
+            (crate::templates::alertify())
+        }),
         Some(maud::html! {
             p {
                 a href="/" class="text-blue-400 hover:underline" {"Home"} " > "
@@ -47,15 +87,36 @@ pub async fn handler(
         }),
         Some(maud::html! {
             div class="flex flex-col items-center justify-center" {
-                details {
-                    summary {
-                        span class="text-red-500" {"Danger Zone"}
+                a href="settings/" {
+                    button class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Book Settings" }
+                }
+                label class="my-2" {
+                    "Push Scoring: "
+                    select name="push-rule" hx-post="push-rule" hx-trigger="change" class="border border-green-300" {
+                        @for (rule, value, label) in [(PushRule::Zero, "zero", "No points"), (PushRule::Refund, "refund", "Refund wager"), (PushRule::Half, "half", "Half points")] {
+                            option value=(value) selected[rule == push_rule] { (label) }
+                        }
                     }
-                    button
-                        hx-delete="."
-                        hx-confirm="Are you sure you wish to delete this book, all chapters, and all picks within FOREVER?"
-                        class="p-0.5 font-bold text-white bg-red-600 rounded hover:bg-red-700" {
-                        "Delete Book"
+                }
+                label class="my-2" {
+                    "Join Requests: "
+                    select name="accepts" hx-post="join-requests" hx-trigger="change" class="border border-green-300" {
+                        option value="false" selected[!settings.accepts_join_requests] { "Closed" }
+                        option value="true" selected[settings.accepts_join_requests] { "Open" }
+                    }
+                }
+
+                @if is_owner {
+                    details {
+                        summary {
+                            span class="text-red-500" {"Danger Zone"}
+                        }
+                        button
+                            hx-delete="."
+                            hx-confirm="Are you sure you wish to delete this book, all chapters, and all picks within FOREVER?"
+                            class="p-0.5 font-bold text-white bg-red-600 rounded hover:bg-red-700" {
+                            "Delete Book"
+                        }
                     }
                 }
 
@@ -72,15 +133,26 @@ pub async fn handler(
                     tbody {
                         tr class="bg-white" {
                             td scope="row" class="px-6 py-4 font-medium text-gray-900 whitespace-nowrap" { (user.username) }
-                            td class="px-6 py-4" { "admin" }
+                            td class="px-6 py-4" { @if is_owner { "owner" } @else { "admin" } }
                             td class="px-6 py-4" { button { "Heavy is The Head" br; "That Wears The Crown" } }
                         }
 
-                        @for user in users {
+                        @for user in &users {
                             tr class="bg-white" hx-target="this" {
                                 td class="px-6 py-4 font-medium text-gray-900 whitespace-nowrap" { (user.username) }
-                                td class="px-6 py-4" { (user.role) }
-                                td class="px-6 py-4" { button hx-post="remove-user" hx-vals={r#"{"user_id":""#(user.id)r#""}"#} class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Remove" } }
+                                td class="px-6 py-4" { (role_form(user.id, &user.role, &chapters, is_owner)) }
+                                td class="px-6 py-4" {
+                                    @if is_owner || !user.role.is_admin() {
+                                        button hx-post="remove-user" hx-vals={r#"{"user_id":""#(user.id)r#""}"#} class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Remove" }
+                                    }
+                                    @if is_owner {
+                                        button
+                                            hx-post="transfer"
+                                            hx-vals={r#"{"user_id":""#(user.id)r#""}"#}
+                                            hx-confirm={"Make " (user.username) " the owner of this book? You will become an admin"}
+                                            class="px-2 py-2 mt-1 ml-1 font-bold text-white bg-red-600 rounded hover:bg-red-700" { "Transfer Ownership" }
+                                    }
+                                }
                             }
                         }
                     }
@@ -103,12 +175,358 @@ pub async fn handler(
                     }
                 }
                 }
+
+                @if !join_requests.is_empty() {
+                    div class="mt-5" {
+                        h2 class="text-lg font-semibold" { "Pending Join Requests" }
+                        table class="text-sm text-left text-gray-500" {
+                            tbody {
+                                @for request in &join_requests {
+                                    tr class="bg-white" hx-target="this" hx-swap="outerHTML" hx-vals={r#"{"user_id":""#(request.id)r#""}"#} {
+                                        td class="px-6 py-4 font-medium text-gray-900" { (request.username) }
+                                        td class="px-6 py-4" { (crate::templates::local_time(request.created_at)) }
+                                        td class="px-6 py-4" {
+                                            button hx-post="join-requests/approve" class="px-2 py-1 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Approve" }
+                                            button hx-post="join-requests/deny" class="px-2 py-1 ml-1 font-bold text-white bg-red-600 rounded hover:bg-red-700" { "Deny" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div class="mt-5" {
+                    h2 class="text-lg font-semibold" { "Invite Links" }
+                    form
+                        hx-post="invites"
+                        hx-target="#invites"
+                        hx-swap="outerHTML"
+                        class="flex flex-wrap items-center justify-center gap-2 my-2" {
+                        select name="role" class="border border-green-300" {
+                            option value="participant" { "Participant" }
+                            option value="guest" { "Guest" }
+                        }
+                        select name="chapter_id" class="border border-green-300" {
+                            option value="" { "Guest chapter..." }
+                            @for chapter in &chapters {
+                                option value=(chapter.chapter_id) { (chapter.title) }
+                            }
+                        }
+                        input type="number" name="max_uses" min="1" placeholder="max uses" class="w-24 border border-green-300";
+                        label class="text-sm" { "Expires " (crate::templates::utc_datetime_input("expires_at", None)) }
+                        button type="submit" class="px-2 py-1 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Create Link" }
+                    }
+                    (invites_table(&invites, &chapters))
+                }
+
+                div class="mt-5" {
+                    h2 class="text-lg font-semibold" { "Point Adjustments" }
+                    form
+                        hx-post="added-points"
+                        hx-target="#added-points"
+                        hx-swap="outerHTML"
+                        hx-on--after-request="if (event.detail.successful) this.reset()"
+                        class="flex flex-wrap items-center justify-center gap-2 my-2" {
+                        select name="user_id" required class="border border-green-300" {
+                            option value=(book_subscription.user_id) { (user.username) }
+                            @for user in &users {
+                                option value=(user.id) { (user.username) }
+                            }
+                        }
+                        select name="direction" class="border border-green-300" {
+                            option value="grant" { "Grant" }
+                            option value="deduct" { "Deduct" }
+                        }
+                        input type="number" name="amount" min="1" required placeholder="points" class="w-20 border border-green-300";
+                        input type="text" name="reason" required placeholder="reason" class="border border-green-300";
+                        button type="submit" class="px-2 py-1 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Apply" }
+                    }
+                    (added_points)
+                }
+
+                div class="mt-5" {
+                    h2 class="text-lg font-semibold" { "Chapter Order" }
+                    p class="text-xs" { "Drag chapters to reorder them. Chapters with the same section are listed together" }
+                    form hx-post="chapter-order" hx-ext="my-enc" hx-trigger="submit, reordered" class="flex flex-col items-center my-2" {
+                        div drag-order {
+                            @for chapter in &chapters {
+                                fieldset name="chapters" me-insert="array" draggable="true" class="flex items-center gap-2 p-2 my-1 bg-white border border-gray-300 rounded-lg shadow-md cursor-move" {
+                                    input type="hidden" name="chapter-id" value=(chapter.chapter_id);
+                                    span class="w-40 truncate" { (chapter.title) }
+                                    input type="number" name="week" min="0" placeholder="week" value=[chapter.week] class="w-16 border border-green-300";
+                                    input type="text" name="section" placeholder="section" value=[chapter.section.as_deref()] class="w-32 border border-green-300";
+                                }
+                            }
+                        }
+                        @if !chapters.is_empty() {
+                            button type="submit" class="px-2 py-1 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Save Chapters" }
+                        }
+                    }
+                }
+
+                div class="mt-5" {
+                    h2 class="text-lg font-semibold" { "Groups" }
+                    p class="text-xs" { "Members of a group share one row on the group leaderboard" }
+                    (groups)
+                }
             }
         }),
         None,
     ))
 }
 
+fn invites_table(invites: &[Invite], chapters: &[Chapter]) -> maud::Markup {
+    maud::html! {
+        table id="invites" class="text-sm text-left text-gray-500" {
+            thead class="text-xs text-gray-700 uppercase bg-gray-100" {
+                tr {
+                    th scope="col" class="px-6 py-3" { "link" }
+                    th scope="col" class="px-6 py-3" { "role" }
+                    th scope="col" class="px-6 py-3" { "uses" }
+                    th scope="col" class="px-6 py-3" { "expires" }
+                    th scope="col" class="px-6 py-3" { "action" }
+                }
+            }
+            tbody {
+                @if invites.is_empty() {
+                    tr class="bg-white" {
+                        td colspan="5" class="px-6 py-4" { "No invite links yet" }
+                    }
+                }
+                @for invite in invites {
+                    tr class="bg-white" {
+                        td class="px-6 py-4" {
+                            button
+                                onclick={"navigator.clipboard.writeText(window.location.origin + '/invite/" (invite.token) "'); alertify.success('Link copied');"}
+                                class="px-2 py-1 text-white bg-blue-500 rounded hover:bg-blue-700" { "Copy Link" }
+                        }
+                        td class="px-6 py-4" {
+                            @match &invite.role.0 {
+                                BookRole::Guest { chapter_ids } => {
+                                    "guest of "
+                                    (chapters.iter().filter(|chapter| chapter_ids.contains(&chapter.chapter_id)).map(|chapter| chapter.title.as_str()).join(", "))
+                                }
+                                _ => "participant",
+                            }
+                        }
+                        td class="px-6 py-4" {
+                            (invite.uses)
+                            @if let Some(max_uses) = invite.max_uses { " / " (max_uses) }
+                        }
+                        td class="px-6 py-4" {
+                            @if let Some(expires_at) = invite.expires_at {
+                                (crate::templates::local_time(expires_at))
+                            } @else {
+                                "Never"
+                            }
+                        }
+                        td class="px-6 py-4" {
+                            button
+                                hx-delete="invites"
+                                hx-vals=(serde_json::json!({ "token": invite.token }))
+                                hx-target="#invites"
+                                hx-swap="outerHTML"
+                                hx-confirm="Revoke this invite link?"
+                                class="px-2 py-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Revoke" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Role picker for a member row. Guests also get a checkbox for each chapter they may see.
+/// Only the owner may promote or demote admins
+fn role_form(user_id: i32, role: &BookRole, chapters: &[Chapter], is_owner: bool) -> maud::Markup {
+    let guest_chapters = match role {
+        BookRole::Owner => return maud::html! { "owner" },
+        BookRole::Admin if !is_owner => return maud::html! { "admin" },
+        BookRole::Guest { chapter_ids } => Some(chapter_ids),
+        _ => None,
+    };
+
+    maud::html! {
+        form hx-post="role" hx-ext="my-enc" hx-trigger="change" hx-swap="outerHTML" {
+            input type="hidden" name="user_id" value=(user_id);
+            select name="role" class="border border-green-300" {
+                @if is_owner {
+                    option value="admin" selected[*role == BookRole::Admin] { "admin" }
+                }
+                option value="participant" selected[*role == BookRole::Participant] { "participant" }
+                option value="guest" selected[guest_chapters.is_some()] { "guest" }
+            }
+            @if let Some(guest_chapters) = guest_chapters {
+                div class="flex flex-col items-start mt-1 text-xs" {
+                    @if chapters.is_empty() {
+                        p { "No chapters yet" }
+                    }
+                    @for chapter in chapters {
+                        label {
+                            input type="checkbox" name="chapter_ids" me-insert="array" value=(chapter.chapter_id) checked[guest_chapters.contains(&chapter.chapter_id)];
+                            " " (chapter.title)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Each named group in the book along with forms to create, rename, fill and delete them
+async fn groups_table(book_id: i32, pool: &sqlx::PgPool) -> Result<maud::Markup, sqlx::Error> {
+    let members = sqlx::query!(
+        "
+        SELECT u.id, u.username
+        FROM users AS u
+        JOIN subscriptions AS s ON u.id = s.user_id
+        WHERE s.book_id = $1
+        ORDER BY u.username
+        ",
+        book_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let groups = sqlx::query!(
+        "
+        SELECT g.name, g.user_id, u.username
+        FROM subscription_groups AS g
+        JOIN users AS u ON g.user_id = u.id
+        WHERE g.book_id = $1
+        ORDER BY g.name, u.username
+        ",
+        book_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let ungrouped = members
+        .iter()
+        .filter(|member| !groups.iter().any(|group| group.user_id == member.id))
+        .collect_vec();
+
+    let member_select = maud::html! {
+        select name="user_id" required class="border border-green-300" {
+            @for member in &ungrouped {
+                option value=(member.id) { (member.username) }
+            }
+        }
+    };
+
+    Ok(maud::html! {
+        div id="groups" class="flex flex-col items-center" {
+            @for (name, group) in &groups.iter().chunk_by(|group| &group.name) {
+                fieldset class="w-full p-2 my-1 text-left border border-green-300 rounded-lg" {
+                    legend class="px-1 font-semibold" { (name) }
+                    form hx-post="groups/rename" hx-target="#groups" hx-swap="outerHTML" class="flex gap-1" {
+                        input type="hidden" name="name" value=(name);
+                        input type="text" name="new_name" required value=(name) class="border border-green-300";
+                        button type="submit" class="px-2 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Rename" }
+                        button
+                            type="button"
+                            hx-delete="groups"
+                            hx-vals=(serde_json::json!({ "name": name }))
+                            hx-target="#groups"
+                            hx-swap="outerHTML"
+                            hx-confirm="Delete this group? Its members will be listed individually"
+                            class="px-2 font-bold text-white bg-red-600 rounded hover:bg-red-700" { "Delete" }
+                    }
+                    ul {
+                        @for member in group {
+                            li class="flex items-center justify-between" {
+                                (member.username)
+                                button
+                                    hx-delete="groups/members"
+                                    hx-vals={r#"{"user_id":""#(member.user_id)r#""}"#}
+                                    hx-target="#groups"
+                                    hx-swap="outerHTML"
+                                    class="px-2 text-white bg-orange-600 rounded hover:bg-orange-700" { "Remove" }
+                            }
+                        }
+                    }
+                    @if !ungrouped.is_empty() {
+                        form hx-post="groups/members" hx-target="#groups" hx-swap="outerHTML" class="flex gap-1" {
+                            input type="hidden" name="name" value=(name);
+                            (member_select)
+                            button type="submit" class="px-2 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Add" }
+                        }
+                    }
+                }
+            }
+            @if !ungrouped.is_empty() {
+                form hx-post="groups/members" hx-target="#groups" hx-swap="outerHTML" class="flex gap-1 my-2" {
+                    input type="text" name="name" required placeholder="new group name" class="border border-green-300";
+                    (member_select)
+                    button type="submit" class="px-2 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Create Group" }
+                }
+            }
+        }
+    })
+}
+
+/// Every manual adjustment in the book, grouped by the user it was applied to
+async fn added_points_table(
+    book_id: i32,
+    pool: &sqlx::PgPool,
+) -> Result<maud::Markup, sqlx::Error> {
+    let adjustments = sqlx::query!(
+        "
+        SELECT a.id, a.user_id, u.username, a.points, a.reason
+        FROM added_points AS a
+        JOIN users AS u ON a.user_id = u.id
+        WHERE a.book_id = $1
+        ORDER BY u.username, a.id
+        ",
+        book_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(maud::html! {
+        table id="added-points" class="text-sm text-left text-gray-500" {
+            thead class="text-xs text-gray-700 uppercase bg-gray-100" {
+                tr {
+                    th scope="col" class="px-6 py-3" { "points" }
+                    th scope="col" class="px-6 py-3" { "reason" }
+                    th scope="col" class="px-6 py-3" { "action" }
+                }
+            }
+            tbody {
+                @if adjustments.is_empty() {
+                    tr class="bg-white" {
+                        td colspan="3" class="px-6 py-4" { "No adjustments yet" }
+                    }
+                }
+                @for (username, user_adjustments) in &adjustments.iter().chunk_by(|adjustment| &adjustment.username) {
+                    @let user_adjustments = user_adjustments.collect_vec();
+                    tr class="bg-green-100" {
+                        th colspan="3" class="px-6 py-2 font-semibold text-gray-900" {
+                            (username) ": " (user_adjustments.iter().map(|adjustment| adjustment.points).sum::<i32>()) " total"
+                        }
+                    }
+                    @for adjustment in user_adjustments {
+                        tr class="bg-white" {
+                            td class={"px-6 py-4 " (if adjustment.points < 0 { "text-red-500" } else { "text-green-600" })} { (format!("{:+}", adjustment.points)) }
+                            td class="px-6 py-4" { (adjustment.reason) }
+                            td class="px-6 py-4" {
+                                button
+                                    hx-delete="added-points"
+                                    hx-vals={r#"{"id":""#(adjustment.id)r#""}"#}
+                                    hx-target="#added-points"
+                                    hx-swap="outerHTML"
+                                    hx-confirm="Remove this adjustment?"
+                                    class="px-2 py-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Remove" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
 #[derive(serde::Deserialize)]
 pub struct AddUserParams {
     user_id: i32,
@@ -138,10 +556,14 @@ pub async fn add_user(
     .map_err(AppError::from)?
     .ok_or(RespErr::new(StatusCode::BAD_REQUEST).user_msg("Could not find user to add"))?;
 
+    let chapters = get_chapters(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?;
+
     Ok(maud::html! {
         tr class="bg-white" hx-target="this" {
             td class="px-6 py-4 font-medium text-gray-900 whitespace-nowrap" { (user_params.username) }
-            td class="px-6 py-4" { "participant" }
+            td class="px-6 py-4" { (role_form(user_params.user_id, &BookRole::Participant, &chapters, book_subscription.role == BookRole::Owner)) }
             td class="px-6 py-4" { button hx-post="remove-user" hx-vals={r#"{"user_id":""#(user_params.user_id)r#""}"#} class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Remove" } }
         }
     })
@@ -212,6 +634,43 @@ pub async fn remove_user(
 ) -> Result<(), AppError<'static>> {
     let pool = &state.pool;
 
+    let role = sqlx::query_scalar!(
+        r#"
+        SELECT role AS "role: sqlx::types::Json<BookRole>"
+        FROM subscriptions
+        WHERE user_id = $1 AND book_id = $2
+        "#,
+        form.user_id,
+        book.id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    match role.map(|role| role.0) {
+        Some(BookRole::Owner) => {
+            return Err(AppError::Unauthorized(
+                "The owner cannot be removed from their book",
+            ))
+        }
+        Some(BookRole::Admin) if book.role != BookRole::Owner => {
+            return Err(AppError::Unauthorized(
+                "Only the owner of this book can remove admins",
+            ))
+        }
+        _ => (),
+    }
+
+    sqlx::query!(
+        "
+        DELETE FROM subscription_groups
+        WHERE user_id = $1 AND book_id = $2
+        ",
+        form.user_id,
+        book.id
+    )
+    .execute(pool)
+    .await?;
+
     sqlx::query!(
         "
         DELETE FROM subscriptions
@@ -230,6 +689,12 @@ pub async fn delete(
     State(state): State<AppStateRef>,
     Extension(book_subscription): Extension<BookSubscription>,
 ) -> Result<impl IntoResponse, AppError<'static>> {
+    if book_subscription.role != BookRole::Owner {
+        return Err(AppError::Unauthorized(
+            "Only the owner of this book can delete it",
+        ));
+    }
+
     let mut transaction = state.pool.begin().await?;
 
     sqlx::query!(
@@ -262,6 +727,26 @@ pub async fn delete(
     .execute(&mut *transaction)
     .await?;
 
+    sqlx::query!(
+        "
+        DELETE FROM added_points
+        WHERE book_id = $1
+        ",
+        book_subscription.id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        DELETE FROM subscription_groups
+        WHERE book_id = $1
+        ",
+        book_subscription.id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
     sqlx::query!(
         "
         DELETE FROM subscriptions
@@ -286,3 +771,741 @@ pub async fn delete(
 
     Ok([("HX-Redirect", "/")].into_response())
 }
+
+#[derive(serde::Deserialize)]
+pub struct PushRuleParams {
+    #[serde(rename = "push-rule")]
+    push_rule: PushRule,
+}
+
+pub async fn push_rule(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(PushRuleParams { push_rule }): Form<PushRuleParams>,
+) -> Result<AppNotification, AppNotification> {
+    sqlx::query!(
+        "
+        UPDATE books
+        SET push_rule = $1
+        WHERE id = $2
+        ",
+        push_rule as PushRule,
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(AppNotification(
+        StatusCode::OK,
+        "Push scoring saved. It applies the next time a chapter is graded".into(),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+pub struct AcceptsJoinRequestsParams {
+    accepts: bool,
+}
+
+pub async fn accepts_join_requests(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(AcceptsJoinRequestsParams { accepts }): Form<AcceptsJoinRequestsParams>,
+) -> Result<AppNotification, AppNotification> {
+    sqlx::query!(
+        "
+        UPDATE books
+        SET accepts_join_requests = $1
+        WHERE id = $2
+        ",
+        accepts,
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(AppNotification(
+        StatusCode::OK,
+        if accepts {
+            "This book now appears in book search".into()
+        } else {
+            "This book no longer takes join requests".into()
+        },
+    ))
+}
+
+#[derive(serde::Deserialize)]
+pub struct JoinRequestParams {
+    user_id: i32,
+}
+
+pub async fn approve_join_request(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(JoinRequestParams { user_id }): Form<JoinRequestParams>,
+) -> Result<impl IntoResponse, AppNotification> {
+    let mut transaction = state.pool.begin().await.map_err(AppError::from)?;
+
+    let result = sqlx::query!(
+        "
+        DELETE FROM join_requests
+        WHERE book_id = $1 AND user_id = $2
+        ",
+        book_subscription.id,
+        user_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppNotification(
+            StatusCode::NOT_FOUND,
+            "That request no longer exists".into(),
+        ));
+    }
+
+    sqlx::query!(
+        "
+        INSERT INTO subscriptions (book_id, user_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, book_id)
+        DO NOTHING
+        ",
+        book_subscription.id,
+        user_id,
+        sqlx::types::Json(BookRole::Participant) as _
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    transaction.commit().await.map_err(AppError::from)?;
+
+    Ok([("HX-Refresh", "true")])
+}
+
+pub async fn deny_join_request(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(JoinRequestParams { user_id }): Form<JoinRequestParams>,
+) -> Result<maud::Markup, AppNotification> {
+    sqlx::query!(
+        "
+        DELETE FROM join_requests
+        WHERE book_id = $1 AND user_id = $2
+        ",
+        book_subscription.id,
+        user_id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(maud::html!())
+}
+
+#[derive(serde::Deserialize)]
+pub struct ChapterOrderSubmission {
+    #[serde(default)]
+    chapters: Vec<ChapterOrderEntry>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct ChapterOrderEntry {
+    chapter_id: String,
+    week: String,
+    section: String,
+}
+
+/// Saves the chapters in the order they were submitted, first chapter listed first
+pub async fn chapter_order(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Json(ChapterOrderSubmission { chapters }): Json<ChapterOrderSubmission>,
+) -> Result<AppNotification, AppNotification> {
+    let count = chapters.len() as i32;
+    let mut ids = Vec::new();
+    let mut sort_orders = Vec::new();
+    let mut weeks = Vec::new();
+    let mut sections = Vec::new();
+    for (i, entry) in chapters.into_iter().enumerate() {
+        let parse_error = || {
+            AppNotification(
+                StatusCode::BAD_REQUEST,
+                "Could not parse chapter order".into(),
+            )
+        };
+
+        ids.push(entry.chapter_id.parse::<i32>().map_err(|_| parse_error())?);
+        sort_orders.push(count - i as i32);
+        weeks.push(match entry.week.trim() {
+            "" => None,
+            week => Some(week.parse::<i32>().map_err(|_| parse_error())?),
+        });
+        sections.push(
+            Some(entry.section.trim())
+                .filter(|section| !section.is_empty())
+                .map(String::from),
+        );
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE chapters AS c
+        SET sort_order = o.sort_order, week = o.week, section = o.section
+        FROM UNNEST($1::INT[], $2::INT[], $3::INT[], $4::TEXT[]) AS o(id, sort_order, week, section)
+        WHERE c.id = o.id AND c.book_id = $5
+        "#,
+        &ids,
+        &sort_orders,
+        &weeks as &[Option<i32>],
+        &sections as &[Option<String>],
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(AppNotification(StatusCode::OK, "Chapters saved".into()))
+}
+
+#[derive(serde::Deserialize)]
+pub struct AddPointsForm {
+    user_id: i32,
+    direction: String,
+    amount: i32,
+    reason: String,
+}
+
+pub async fn add_points(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(AddPointsForm {
+        user_id,
+        direction,
+        amount,
+        reason,
+    }): Form<AddPointsForm>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "A reason is required".into(),
+        ));
+    }
+    if amount < 1 {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Points must be a positive number".into(),
+        ));
+    }
+
+    let points = match direction.as_str() {
+        "grant" => amount,
+        "deduct" => -amount,
+        _ => {
+            return Err(AppNotification(
+                StatusCode::BAD_REQUEST,
+                "Points must be granted or deducted".into(),
+            ))
+        }
+    };
+
+    sqlx::query!(
+        "
+        INSERT INTO added_points (user_id, book_id, points, reason)
+        SELECT user_id, book_id, $3, $4
+        FROM subscriptions
+        WHERE user_id = $1 AND book_id = $2
+        RETURNING id
+        ",
+        user_id,
+        book_subscription.id,
+        points,
+        reason
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)?
+    .ok_or(AppNotification(
+        StatusCode::BAD_REQUEST,
+        "User is not a member of this book".into(),
+    ))?;
+
+    Ok(added_points_table(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RemovePointsParams {
+    id: i32,
+}
+
+pub async fn remove_points(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(RemovePointsParams { id }): Query<RemovePointsParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    sqlx::query!(
+        "
+        DELETE FROM added_points
+        WHERE id = $1 AND book_id = $2
+        ",
+        id,
+        book_subscription.id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(added_points_table(book_subscription.id, pool).await?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct GroupMemberForm {
+    name: String,
+    user_id: i32,
+}
+
+/// Puts a member into the named group, creating it if needed. Members belong to at most one group
+pub async fn add_group_member(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(GroupMemberForm { name, user_id }): Form<GroupMemberForm>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Group name cannot be empty".into(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await.map_err(AppError::from)?;
+
+    sqlx::query!(
+        "
+        DELETE FROM subscription_groups
+        WHERE user_id = $1 AND book_id = $2
+        ",
+        user_id,
+        book_subscription.id
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    sqlx::query!(
+        "
+        INSERT INTO subscription_groups (name, user_id, book_id)
+        SELECT $1, user_id, book_id
+        FROM subscriptions
+        WHERE user_id = $2 AND book_id = $3
+        RETURNING user_id
+        ",
+        name,
+        user_id,
+        book_subscription.id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(AppError::from)?
+    .ok_or(AppNotification(
+        StatusCode::BAD_REQUEST,
+        "User is not a member of this book".into(),
+    ))?;
+
+    transaction.commit().await.map_err(AppError::from)?;
+
+    Ok(groups_table(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RemoveGroupMemberParams {
+    user_id: i32,
+}
+
+pub async fn remove_group_member(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(RemoveGroupMemberParams { user_id }): Query<RemoveGroupMemberParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    sqlx::query!(
+        "
+        DELETE FROM subscription_groups
+        WHERE user_id = $1 AND book_id = $2
+        ",
+        user_id,
+        book_subscription.id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(groups_table(book_subscription.id, pool).await?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RenameGroupForm {
+    name: String,
+    new_name: String,
+}
+
+pub async fn rename_group(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(RenameGroupForm { name, new_name }): Form<RenameGroupForm>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Group name cannot be empty".into(),
+        ));
+    }
+
+    let name_taken = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM subscription_groups
+            WHERE book_id = $1 AND name = $2 AND name != $3
+        ) AS "exists!"
+        "#,
+        book_subscription.id,
+        new_name,
+        name
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(AppError::from)?;
+
+    if name_taken {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Another group already has that name".into(),
+        ));
+    }
+
+    sqlx::query!(
+        "
+        UPDATE subscription_groups
+        SET name = $1
+        WHERE book_id = $2 AND name = $3
+        ",
+        new_name,
+        book_subscription.id,
+        name
+    )
+    .execute(pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(groups_table(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteGroupParams {
+    name: String,
+}
+
+pub async fn delete_group(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(DeleteGroupParams { name }): Query<DeleteGroupParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    sqlx::query!(
+        "
+        DELETE FROM subscription_groups
+        WHERE book_id = $1 AND name = $2
+        ",
+        book_subscription.id,
+        name
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(groups_table(book_subscription.id, pool).await?)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RoleSubmission {
+    user_id: String,
+    role: String,
+    #[serde(default)]
+    chapter_ids: Vec<String>,
+}
+
+pub async fn change_role(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Json(RoleSubmission {
+        user_id,
+        role,
+        chapter_ids,
+    }): Json<RoleSubmission>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let user_id = user_id
+        .parse::<i32>()
+        .map_err(|_| AppNotification(StatusCode::BAD_REQUEST, "Could not parse user id".into()))?;
+
+    if user_id == book_subscription.user_id {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "You cannot change your own role".into(),
+        ));
+    }
+
+    let is_owner = book_subscription.role == BookRole::Owner;
+    if role == "admin" && !is_owner {
+        return Err(AppNotification(
+            StatusCode::UNAUTHORIZED,
+            "Only the owner can promote admins".into(),
+        ));
+    }
+
+    let role = match role.as_str() {
+        "admin" => BookRole::Admin,
+        "participant" => BookRole::Participant,
+        "guest" => BookRole::Guest {
+            chapter_ids: chapter_ids
+                .iter()
+                .map(|id| id.parse::<i32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    AppNotification(StatusCode::BAD_REQUEST, "Could not parse chapter id".into())
+                })?,
+        },
+        _ => {
+            return Err(AppNotification(
+                StatusCode::BAD_REQUEST,
+                "Unknown role".into(),
+            ))
+        }
+    };
+
+    sqlx::query!(
+        r#"
+        UPDATE subscriptions
+        SET role = $1
+        WHERE
+            user_id = $2
+            AND book_id = $3
+            AND role != '"owner"'::JSONB
+            AND ($4 OR role != '"admin"'::JSONB)
+        RETURNING user_id
+        "#,
+        sqlx::types::Json(&role) as _,
+        user_id,
+        book_subscription.id,
+        is_owner
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)?
+    .ok_or(AppNotification(
+        StatusCode::BAD_REQUEST,
+        "Could not change this member's role".into(),
+    ))?;
+
+    let chapters = get_chapters(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(role_form(user_id, &role, &chapters, is_owner))
+}
+
+#[derive(serde::Deserialize)]
+pub struct TransferForm {
+    user_id: i32,
+}
+
+/// Hands the book to another member. The previous owner stays on as an admin
+pub async fn transfer(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(TransferForm { user_id }): Form<TransferForm>,
+) -> Result<impl IntoResponse, AppNotification> {
+    if user_id == book_subscription.user_id {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "You already own this book".into(),
+        ));
+    }
+
+    let mut transaction = state.pool.begin().await.map_err(AppError::from)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE subscriptions
+        SET role = '"owner"'::JSONB
+        WHERE user_id = $1 AND book_id = $2
+        RETURNING user_id
+        "#,
+        user_id,
+        book_subscription.id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(AppError::from)?
+    .ok_or(AppNotification(
+        StatusCode::BAD_REQUEST,
+        "User is not a member of this book".into(),
+    ))?;
+
+    sqlx::query!(
+        r#"
+        UPDATE subscriptions
+        SET role = '"admin"'::JSONB
+        WHERE user_id = $1 AND book_id = $2
+        "#,
+        book_subscription.user_id,
+        book_subscription.id
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    transaction.commit().await.map_err(AppError::from)?;
+
+    Ok([("HX-Refresh", "true")])
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateInviteForm {
+    role: String,
+    chapter_id: String,
+    max_uses: String,
+    expires_at: String,
+}
+
+pub async fn create_invite(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(CreateInviteForm {
+        role,
+        chapter_id,
+        max_uses,
+        expires_at,
+    }): Form<CreateInviteForm>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let role = match role.as_str() {
+        "participant" => BookRole::Participant,
+        "guest" => BookRole::Guest {
+            chapter_ids: vec![chapter_id.parse::<i32>().map_err(|_| {
+                AppNotification(
+                    StatusCode::BAD_REQUEST,
+                    "Guest invites need a chapter".into(),
+                )
+            })?],
+        },
+        _ => {
+            return Err(AppNotification(
+                StatusCode::BAD_REQUEST,
+                "Invites can only be for participants or guests".into(),
+            ))
+        }
+    };
+
+    let max_uses = match max_uses.trim() {
+        "" => None,
+        max_uses => Some(
+            max_uses
+                .parse::<i32>()
+                .ok()
+                .filter(|max_uses| *max_uses > 0)
+                .ok_or(AppNotification(
+                    StatusCode::BAD_REQUEST,
+                    "Max uses must be a positive number".into(),
+                ))?,
+        ),
+    };
+
+    let expires_at = match expires_at.as_str() {
+        "" => None,
+        expires_at => Some(
+            time::OffsetDateTime::parse(expires_at, &time::format_description::well_known::Rfc3339)
+                .map_err(|_| {
+                    AppNotification(
+                        StatusCode::BAD_REQUEST,
+                        "Could not parse expiry time".into(),
+                    )
+                })?,
+        ),
+    };
+
+    sqlx::query!(
+        "
+        INSERT INTO book_invites (book_id, role, max_uses, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ",
+        book_subscription.id,
+        sqlx::types::Json(role) as _,
+        max_uses,
+        expires_at
+    )
+    .execute(pool)
+    .await
+    .map_err(AppError::from)?;
+
+    let invites = get_invites(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?;
+    let chapters = get_chapters(book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(invites_table(&invites, &chapters))
+}
+
+#[derive(serde::Deserialize)]
+pub struct RevokeInviteParams {
+    token: String,
+}
+
+pub async fn revoke_invite(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(RevokeInviteParams { token }): Query<RevokeInviteParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    sqlx::query!(
+        "
+        DELETE FROM book_invites
+        WHERE token = $1 AND book_id = $2
+        ",
+        token,
+        book_subscription.id
+    )
+    .execute(pool)
+    .await?;
+
+    let invites = get_invites(book_subscription.id, pool).await?;
+    let chapters = get_chapters(book_subscription.id, pool).await?;
+
+    Ok(invites_table(&invites, &chapters))
+}
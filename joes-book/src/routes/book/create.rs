@@ -2,21 +2,32 @@ use axum::{http::StatusCode, response::Redirect, Form};
 use axum_ctx::RespErr;
 use serde::Deserialize;
 
-use crate::{auth::AuthSession, db::book::BookRole, AppError};
+use crate::{
+    auth::AuthSession,
+    db::book::{BookRole, BookType, MAX_BOOK_NAME_LEN},
+    AppError,
+};
 
 #[derive(Deserialize)]
 pub struct Params {
     #[serde(rename(deserialize = "book-name"))]
     book_name: String,
+    #[serde(default, rename(deserialize = "book-type"))]
+    book_type: BookType,
 }
 
 pub async fn handler(
     auth_session: AuthSession,
-    Form(Params { book_name }): Form<Params>,
+    Form(Params {
+        book_name,
+        book_type,
+    }): Form<Params>,
 ) -> Result<Redirect, RespErr> {
-    if book_name.len() > 30 {
-        return Err(RespErr::new(StatusCode::BAD_REQUEST)
-            .user_msg("Book name must be less than 30 characters"));
+    let book_name = book_name.trim();
+    if book_name.is_empty() || book_name.chars().count() > MAX_BOOK_NAME_LEN {
+        return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(format!(
+            "Book name must be between 1 and {MAX_BOOK_NAME_LEN} characters"
+        )));
     }
 
     let user = auth_session.user.ok_or(AppError::BackendUser)?;
@@ -25,11 +36,12 @@ pub async fn handler(
     let mut transaction = pool.begin().await.map_err(AppError::from)?;
 
     let record = sqlx::query!(
-        "INSERT INTO books (name)
-            VALUES ($1)
+        "INSERT INTO books (name, kind)
+            VALUES ($1, $2)
             ON CONFLICT DO NOTHING
             RETURNING id",
-        book_name
+        book_name,
+        book_type as BookType
     )
     .fetch_one(&mut *transaction)
     .await
@@ -40,7 +52,7 @@ pub async fn handler(
             VALUES ($1, $2, $3)",
         user.id,
         record.id,
-        serde_json::to_value(BookRole::Admin)
+        serde_json::to_value(BookRole::Owner)
             .map_err(|e| RespErr::new(StatusCode::INTERNAL_SERVER_ERROR).log_msg(e.to_string()))?
     )
     .execute(&mut *transaction)
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Query, State},
+    Form,
+};
+use axum_ctx::StatusCode;
+
+use crate::{auth::AuthSession, AppError, AppNotification, AppStateRef};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct BookSearchParams {
+    name: String,
+}
+
+/// Books open to join requests that the user isn't already in
+pub async fn search(
+    auth_session: AuthSession,
+    State(state): State<AppStateRef>,
+    Query(BookSearchParams { name }): Query<BookSearchParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let user = auth_session.user.ok_or(AppError::BackendUser)?;
+    let pool = &state.pool;
+
+    if name.is_empty() {
+        return Ok(maud::html!());
+    }
+
+    let books = sqlx::query!(
+        r#"
+        SELECT b.id, b.name, r.user_id IS NOT NULL AS "requested!"
+        FROM books AS b
+        LEFT JOIN join_requests AS r ON b.id = r.book_id AND r.user_id = $2
+        WHERE
+            b.accepts_join_requests
+            AND NOT b.archived
+            AND LOWER(b.name) LIKE '%' || LOWER($1) || '%'
+            AND NOT EXISTS (
+                SELECT 1
+                FROM subscriptions
+                WHERE subscriptions.book_id = b.id AND subscriptions.user_id = $2
+            )
+        ORDER BY b.name
+        LIMIT 10
+        "#,
+        name,
+        user.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(maud::html! {
+        @if books.is_empty() {
+            li { "No books found" }
+        }
+        @for book in books {
+            li class="flex items-center justify-between gap-2 p-1" {
+                (book.name)
+                @if book.requested {
+                    span class="text-sm text-gray-500" { "Requested" }
+                } @else {
+                    button
+                        hx-post="/book/join"
+                        hx-vals={r#"{"book_id":""#(book.id)r#""}"#}
+                        hx-target="this"
+                        hx-swap="outerHTML"
+                        class="px-2 py-1 text-sm font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Request to Join" }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct JoinRequestForm {
+    book_id: i32,
+}
+
+pub async fn request(
+    auth_session: AuthSession,
+    State(state): State<AppStateRef>,
+    Form(JoinRequestForm { book_id }): Form<JoinRequestForm>,
+) -> Result<maud::Markup, AppNotification> {
+    let user = auth_session.user.ok_or(AppError::BackendUser)?;
+    let pool = &state.pool;
+
+    let result = sqlx::query!(
+        "
+        INSERT INTO join_requests (book_id, user_id)
+        SELECT id, $2
+        FROM books
+        WHERE id = $1 AND accepts_join_requests AND NOT archived
+        ON CONFLICT (book_id, user_id) DO NOTHING
+        ",
+        book_id,
+        user.id
+    )
+    .execute(pool)
+    .await
+    .map_err(AppError::from)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "This book is not taking join requests".into(),
+        ));
+    }
+
+    Ok(maud::html! {
+        span class="text-sm text-gray-500" { "Requested" }
+    })
+}
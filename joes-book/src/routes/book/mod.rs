@@ -1,12 +1,14 @@
 pub mod admin;
 pub mod create;
+pub mod join;
 pub mod page;
+pub mod settings;
 
 pub mod mw {
     use axum::{
         body::Body,
         extract::{Path, Request},
-        http::{Response, StatusCode},
+        http::{Method, Response, StatusCode},
         middleware::Next,
         response::{ErrorResponse, IntoResponse, Redirect},
         Extension,
@@ -16,7 +18,7 @@ pub mod mw {
     use crate::{
         auth::{AuthSession, BackendPgDB},
         db::book::{get_book, BookRole, BookSubscription},
-        AppError,
+        AppError, AppNotification,
     };
 
     #[derive(serde::Deserialize)]
@@ -57,7 +59,7 @@ pub mod mw {
         request: Request,
         next: Next,
     ) -> Result<Response<Body>, RespErr> {
-        if book_subscription.role != BookRole::Admin {
+        if !book_subscription.role.is_admin() {
             return Err(
                 AppError::Unauthorized("You do not have admin privilages for this book").into(),
             );
@@ -65,4 +67,32 @@ pub mod mw {
 
         Ok(next.run(request).await)
     }
+
+    pub async fn require_owner(
+        Extension(book_subscription): Extension<BookSubscription>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response<Body>, RespErr> {
+        if book_subscription.role != BookRole::Owner {
+            return Err(AppError::Unauthorized("Only the owner of this book can do that").into());
+        }
+
+        Ok(next.run(request).await)
+    }
+
+    /// Archived books can still be viewed, but nothing in them can be changed
+    pub async fn reject_archived(
+        Extension(book_subscription): Extension<BookSubscription>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response<Body>, AppNotification> {
+        if book_subscription.archived && request.method() != Method::GET {
+            return Err(AppNotification(
+                StatusCode::LOCKED,
+                "This book is archived".into(),
+            ));
+        }
+
+        Ok(next.run(request).await)
+    }
 }
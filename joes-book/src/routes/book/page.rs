@@ -1,9 +1,12 @@
-use axum::{extract::State, Extension};
+use axum::{
+    extract::{Query, State},
+    Extension,
+};
 
 use crate::{
     auth::{AuthSession, BackendPgDB},
     db::{
-        book::{BookRole, BookSubscription},
+        book::{BookRole, BookSubscription, BookType},
         chapter::get_chapters,
     },
     templates::chapter_list,
@@ -18,6 +21,12 @@ pub async fn handler(
     let BackendPgDB(pool) = auth_session.backend;
 
     let chapters = get_chapters(book_subscription.id, &pool).await?;
+    let details = sqlx::query!(
+        "SELECT description, rules, season FROM books WHERE id = $1",
+        book_subscription.id
+    )
+    .fetch_one(&pool)
+    .await?;
     let guest_chapters = if let BookRole::Guest { ref chapter_ids } = book_subscription.role {
         Some(chapter_ids)
     } else {
@@ -37,16 +46,33 @@ pub async fn handler(
         }),
         Some(maud::html! {
             h1 class="text-4xl font-extrabold" {(book_subscription.name)}
-            @if book_subscription.role == BookRole::Admin {
+            @if let Some(season) = details.season {
+                p class="text-gray-500" { (season) }
+            }
+            @if book_subscription.archived {
+                p class="p-2 m-2 text-white bg-gray-500 rounded" { "This book is archived and read-only" }
+            }
+            @if let Some(description) = details.description {
+                p class="my-2 whitespace-pre-line" { (description) }
+            }
+            @if let Some(rules) = details.rules {
+                details class="my-2" {
+                    summary { "Rules" }
+                    p class="whitespace-pre-line" { (rules) }
+                }
+            }
+            @if book_subscription.role.is_admin() {
                 div class="flex justify-center" {
                     fieldset class="w-1/2 border border-orange-600" {
                         legend class="ml-3" { "Admin Section" }
-                        a href="chapter/create/" {
-                            button class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
-                                "Create New Chapter"
+                        @if !book_subscription.archived {
+                            a href="chapter/create/" {
+                                button class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                                    "Create New Chapter"
+                                }
                             }
+                            br;
                         }
-                        br;
                         a href="admin/" {
                             button class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
                                 "Admin"
@@ -78,26 +104,53 @@ pub async fn handler(
     ))
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardView {
+    /// Members of a subscription group share a single row
+    #[default]
+    Group,
+    Individual,
+}
+
+#[derive(serde::Deserialize)]
+pub struct LeaderboardParams {
+    #[serde(default)]
+    view: LeaderboardView,
+}
+
 pub async fn leaderboard(
     State(state): State<AppStateRef>,
     book_subscription: Extension<BookSubscription>,
+    Query(LeaderboardParams { view }): Query<LeaderboardParams>,
 ) -> Result<maud::Markup, AppError<'static>> {
     let pool = &state.pool;
 
+    if book_subscription.kind == BookType::Survivor {
+        return survivor_leaderboard(book_subscription.id, pool).await;
+    }
+
     let rankings = sqlx::query!(
         r#"
         SELECT
             USERNAME AS "username!",
             EARNED_POINTS::INT AS "earned_points!",
             ADDED_POINTS::INT AS "added_points!",
-            EARNED_POINTS::INT + ADDED_POINTS::INT AS "total_points!"
+            EARNED_POINTS::INT + ADDED_POINTS::INT AS "total_points!",
+            ADDED_REASONS,
+            WINS::INT AS "wins!",
+            LOSSES::INT AS "losses!"
         FROM
             (
                 -- Join user earned points and added points
                 SELECT
                     USERNAME,
                     COALESCE(SUM(EARNED_POINTS), 0) AS EARNED_POINTS,
-                    COALESCE(SUM(ADDED_POINTS), 0) AS ADDED_POINTS
+                    COALESCE(SUM(ADDED_POINTS), 0) AS ADDED_POINTS,
+                    STRING_AGG(ADDED_REASONS, '; ') AS ADDED_REASONS,
+                    COALESCE(SUM(RECORDS.WINS), 0) AS WINS,
+                    COALESCE(SUM(RECORDS.LOSSES), 0) AS LOSSES,
+                    MIN(TIEBREAKS.DISTANCE) AS TIEBREAKER_DISTANCE
                 FROM
                     (
                         SELECT
@@ -109,7 +162,12 @@ pub async fn leaderboard(
                                 -- Get earned points
                                 SELECT
                                     BOOK_USERS.USER_ID,
-                                    COALESCE(USER_GROUPS.NAME, BOOK_USERS.USERNAME) AS USERNAME,
+                                    COALESCE(
+                                        CASE
+                                            WHEN $2 THEN USER_GROUPS.NAME
+                                        END,
+                                        BOOK_USERS.USERNAME
+                                    ) AS USERNAME,
                                     COALESCE(PICK_POINTS.POINTS, 0) AS EARNED_POINTS
                                 FROM
                                     -- Get users in group
@@ -152,7 +210,13 @@ pub async fn leaderboard(
                     LEFT JOIN (
                         SELECT
                             USER_ID,
-                            SUM(ADDED_POINTS.POINTS) AS ADDED_POINTS
+                            SUM(ADDED_POINTS.POINTS) AS ADDED_POINTS,
+                            STRING_AGG(
+                                FORMAT('%s (%s)', ADDED_POINTS.REASON, TO_CHAR(ADDED_POINTS.POINTS, 'FMSG999999')),
+                                '; '
+                                ORDER BY
+                                    ADDED_POINTS.ID
+                            ) AS ADDED_REASONS
                         FROM
                             ADDED_POINTS
                         WHERE
@@ -160,25 +224,98 @@ pub async fn leaderboard(
                         GROUP BY
                             USER_ID
                     ) AS ADDED_POINTS ON GROUPED_EARNED_POINTS.USER_ID = ADDED_POINTS.USER_ID
+                    -- Get graded results, with spread groups counting once per game
+                    LEFT JOIN (
+                        SELECT
+                            PICKS.USER_ID,
+                            COUNT(*) FILTER (
+                                WHERE
+                                    RESULT = 'true'
+                            ) AS WINS,
+                            COUNT(*) FILTER (
+                                WHERE
+                                    RESULT = 'false'
+                            ) AS LOSSES
+                        FROM
+                            PICKS
+                            CROSS JOIN LATERAL JSONB_ARRAY_ELEMENTS(
+                                CASE JSONB_TYPEOF(PICKS.CORRECT)
+                                    WHEN 'array' THEN PICKS.CORRECT
+                                    ELSE JSONB_BUILD_ARRAY(PICKS.CORRECT)
+                                END
+                            ) AS RESULT
+                        WHERE
+                            PICKS.BOOK_ID = $1
+                            AND PICKS.CORRECT IS NOT NULL
+                        GROUP BY
+                            PICKS.USER_ID
+                    ) AS RECORDS ON GROUPED_EARNED_POINTS.USER_ID = RECORDS.USER_ID
+                    -- Get distance from the most recently answered tiebreaker
+                    LEFT JOIN (
+                        SELECT
+                            TIEBREAKERS.USER_ID,
+                            ABS(TIEBREAKERS.GUESS - CHAPTERS.TIEBREAKER_ANSWER) AS DISTANCE
+                        FROM
+                            TIEBREAKERS
+                            JOIN CHAPTERS ON TIEBREAKERS.CHAPTER_ID = CHAPTERS.ID
+                        WHERE
+                            CHAPTERS.ID = (
+                                SELECT
+                                    ID
+                                FROM
+                                    CHAPTERS
+                                WHERE
+                                    BOOK_ID = $1
+                                    AND TIEBREAKER_ANSWER IS NOT NULL
+                                ORDER BY
+                                    SORT_ORDER DESC,
+                                    CREATED_AT DESC
+                                LIMIT
+                                    1
+                            )
+                    ) AS TIEBREAKS ON GROUPED_EARNED_POINTS.USER_ID = TIEBREAKS.USER_ID
                 GROUP BY
                     GROUPED_EARNED_POINTS.USERNAME
             ) AS SUB1
         ORDER BY
-            "total_points!" DESC
+            "total_points!" DESC,
+            TIEBREAKER_DISTANCE NULLS LAST,
+            USERNAME
         "#,
-        book_subscription.id
+        book_subscription.id,
+        view == LeaderboardView::Group
     )
     .fetch_all(pool)
     .await?;
 
+    let has_groups = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM subscription_groups WHERE book_id = $1) AS "exists!""#,
+        book_subscription.id
+    )
+    .fetch_one(pool)
+    .await?;
+
     Ok(maud::html! {
-        div class="flex justify-center w-max" {
+        div id="leaderboard" class="flex flex-col items-center w-max" {
+            @if has_groups {
+                div class="flex gap-1 my-1" {
+                    @for (option, label) in [(LeaderboardView::Group, "group"), (LeaderboardView::Individual, "individual")] {
+                        button
+                            hx-get={"leaderboard?view="(label)}
+                            hx-target="#leaderboard"
+                            hx-swap="outerHTML"
+                            disabled[option == view]
+                            class="px-2 py-1 text-xs capitalize bg-green-300 rounded disabled:bg-green-500 disabled:font-bold" { (label) }
+                    }
+                }
+            }
             table class="text-sm w-max" {
                 thead class="text-xs text-gray-700 uppercase bg-green-400" {
                     tr {
                         th scope="col" class="px-6 py-3" { "Rank" }
                         th scope="col" class="px-6 py-3" { "User" }
                         th scope="col" class="px-6 py-3" { "Total Points" }
+                        th scope="col" class="px-6 py-3" { "Record" }
                     }
                 }
 
@@ -189,11 +326,68 @@ pub async fn leaderboard(
                             td class="px-6 py-4" {
                                 (rank.username)
                                 br;
-                                @if rank.added_points > 0 {
+                                @if rank.added_points != 0 {
                                     span class="text-red-500" {"Added Points: "(rank.added_points)}
                                 }
+                                @if let Some(reasons) = &rank.added_reasons {
+                                    p class="text-xs text-gray-500" {(reasons)}
+                                }
                             }
                             td class="px-6 py-4" {(rank.total_points)}
+                            td class="px-6 py-4" {(rank.wins) "-" (rank.losses)}
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+async fn survivor_leaderboard(
+    book_id: i32,
+    pool: &sqlx::PgPool,
+) -> Result<maud::Markup, AppError<'static>> {
+    let standings = sqlx::query!(
+        r#"
+        SELECT
+            USERS.USERNAME,
+            CHAPTERS.TITLE AS "eliminated_in?"
+        FROM
+            SUBSCRIPTIONS
+            JOIN USERS ON SUBSCRIPTIONS.USER_ID = USERS.ID
+            LEFT JOIN CHAPTERS ON SUBSCRIPTIONS.ELIMINATED_IN = CHAPTERS.ID
+        WHERE
+            SUBSCRIPTIONS.BOOK_ID = $1
+        ORDER BY
+            SUBSCRIPTIONS.ELIMINATED_IN IS NOT NULL,
+            CHAPTERS.SORT_ORDER DESC,
+            CHAPTERS.CREATED_AT DESC,
+            USERS.USERNAME
+        "#,
+        book_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(maud::html! {
+        div class="flex justify-center w-max" {
+            table class="text-sm w-max" {
+                thead class="text-xs text-gray-700 uppercase bg-green-400" {
+                    tr {
+                        th scope="col" class="px-6 py-3" { "User" }
+                        th scope="col" class="px-6 py-3" { "Status" }
+                    }
+                }
+
+                tbody {
+                    @for standing in standings {
+                        tr class="bg-white" {
+                            td class="px-6 py-4" {(standing.username)}
+                            @if let Some(eliminated_in) = standing.eliminated_in {
+                                td class="px-6 py-4 text-red-500" { "Eliminated in " (eliminated_in) }
+                            } @else {
+                                td class="px-6 py-4 font-semibold text-green-600" { "Surviving" }
+                            }
                         }
                     }
                 }
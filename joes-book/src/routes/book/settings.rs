@@ -0,0 +1,167 @@
+use axum::{extract::State, response::IntoResponse, Extension, Form};
+use axum_ctx::StatusCode;
+
+use crate::{
+    auth::AuthSession,
+    db::book::{BookSubscription, MAX_BOOK_NAME_LEN},
+    templates::authenticated,
+    AppError, AppNotification, AppStateRef,
+};
+
+pub async fn get(
+    auth_session: AuthSession,
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let user = auth_session.user.ok_or(AppError::BackendUser)?;
+
+    let book = sqlx::query!(
+        "
+        SELECT name, description, rules, season, archived
+        FROM books
+        WHERE id = $1
+        ",
+        book_subscription.id
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(authenticated(
+        &user.username,
+        Some(format!("{} - Settings", book.name).as_str()),
+        None,
+        Some(crate::templates::alertify()),
+        Some(maud::html! {
+            p {
+                a href="/" class="text-blue-400 hover:underline" {"Home"} " > "
+                a href="../.." class="text-blue-400 hover:underline" { (book.name) } " > "
+                a href=".." class="text-blue-400 hover:underline" { "Admin" } " > "
+                a { "Settings" }
+            }
+        }),
+        Some(maud::html! {
+            div class="flex flex-col items-center justify-center" {
+                @if book.archived {
+                    p class="p-2 my-2 text-white bg-gray-500 rounded" { "This book is archived and read-only" }
+                } @else {
+                    form hx-post="." class="flex flex-col w-full max-w-lg gap-2 my-2" {
+                        label {
+                            p { "Name" }
+                            input type="text" name="name" required maxlength=(MAX_BOOK_NAME_LEN) value=(book.name) class="w-full border border-green-300";
+                        }
+                        label {
+                            p { "Season" }
+                            input type="text" name="season" placeholder="e.g. 2026" value=[book.season] class="w-full border border-green-300";
+                        }
+                        label {
+                            p { "Description" }
+                            textarea name="description" rows="3" class="w-full border border-green-300" { (book.description.unwrap_or_default()) }
+                        }
+                        label {
+                            p { "Rules" }
+                            textarea name="rules" rows="6" class="w-full border border-green-300" { (book.rules.unwrap_or_default()) }
+                        }
+                        button type="submit" class="px-2 py-1 font-bold text-white bg-green-500 rounded hover:bg-green-700" { "Save" }
+                    }
+                }
+
+                @if book.archived {
+                    button
+                        hx-post="archive"
+                        hx-vals=r#"{"archived":"false"}"#
+                        class="px-2 py-2 mt-3 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Unarchive Book" }
+                } @else {
+                    button
+                        hx-post="archive"
+                        hx-vals=r#"{"archived":"true"}"#
+                        hx-confirm="Archive this book? It will become read-only until it is unarchived"
+                        class="px-2 py-2 mt-3 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Archive Book" }
+                }
+            }
+        }),
+        None,
+    ))
+}
+
+#[derive(serde::Deserialize)]
+pub struct SettingsForm {
+    name: String,
+    season: String,
+    description: String,
+    rules: String,
+}
+
+pub async fn post(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(SettingsForm {
+        name,
+        season,
+        description,
+        rules,
+    }): Form<SettingsForm>,
+) -> Result<AppNotification, AppNotification> {
+    if book_subscription.archived {
+        return Err(AppNotification(
+            StatusCode::FORBIDDEN,
+            "Unarchive the book to change its settings".into(),
+        ));
+    }
+
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_BOOK_NAME_LEN {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            format!("Book name must be between 1 and {MAX_BOOK_NAME_LEN} characters"),
+        ));
+    }
+
+    sqlx::query!(
+        "
+        UPDATE books
+        SET name = $1, season = $2, description = $3, rules = $4
+        WHERE id = $5
+        ",
+        name,
+        optional(&season),
+        optional(&description),
+        optional(&rules),
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(AppNotification(StatusCode::OK, "Settings saved".into()))
+}
+
+/// Blank fields are stored as NULL
+fn optional(text: &str) -> Option<&str> {
+    Some(text.trim()).filter(|text| !text.is_empty())
+}
+
+#[derive(serde::Deserialize)]
+pub struct ArchiveParams {
+    archived: bool,
+}
+
+pub async fn archive(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Form(ArchiveParams { archived }): Form<ArchiveParams>,
+) -> Result<impl IntoResponse, AppNotification> {
+    sqlx::query!(
+        "
+        UPDATE books
+        SET archived = $1
+        WHERE id = $2
+        ",
+        archived,
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok([("HX-Refresh", "true")])
+}
@@ -3,16 +3,19 @@ use std::{borrow::BorrowMut, collections::HashMap};
 use axum::{
     extract::{Query, State},
     response::IntoResponse,
-    Extension, Json,
+    Extension, Form, Json,
 };
 use axum_ctx::{RespErr, RespErrCtx, RespErrExt, StatusCode};
+use itertools::Itertools;
 
 use crate::{
     auth::{AuthSession, BackendPgDB},
     db::{
-        book::BookSubscription,
+        book::{BookRole, BookSubscription},
         chapter::Chapter,
         event::{get_events, EventContent},
+        spread::SpreadGroup,
+        survivor::apply_eliminations,
         team::get_chapter_teams,
     },
     AppError, AppNotification, AppStateRef,
@@ -46,6 +49,8 @@ pub async fn get(
 #[derive(Debug, serde::Deserialize)]
 pub struct AnswerSubmission {
     events: Vec<AnswerEvent>,
+    #[serde(default, rename = "tiebreaker-answer")]
+    tiebreaker_answer: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -65,6 +70,11 @@ pub struct AnswerEvent {
 pub enum AnswerEventContent {
     SpreadGroup { selections: Vec<String> },
     UserInput { choices: Option<Vec<String>> },
+    Moneyline { selection: String },
+    OverUnder { selection: String },
+    MultipleChoice { selection: String },
+    Numeric { answer: String },
+    Survivor { winners: Vec<String> },
 }
 
 pub async fn post(
@@ -72,6 +82,7 @@ pub async fn post(
     Extension(chapter): Extension<Chapter>,
     Json(AnswerSubmission {
         events: event_submissions,
+        tiebreaker_answer,
     }): Json<AnswerSubmission>,
 ) -> Result<AppNotification, AppNotification> {
     let pool = &state.pool;
@@ -97,7 +108,9 @@ pub async fn post(
             )?;
             match (event.contents.0.borrow_mut(), submission) {
                 (
-                    EventContent::SpreadGroup(ref mut spreads),
+                    EventContent::SpreadGroup(SpreadGroup {
+                        ref mut spreads, ..
+                    }),
                     AnswerEventContent::SpreadGroup { selections },
                 ) => {
                     if spreads.len() != selections.len() {
@@ -117,6 +130,59 @@ pub async fn post(
                         Some(choices.unwrap_or_default().into_iter().collect());
                     Ok((event.id, serde_json::to_value(event.contents).unwrap()))
                 }
+                (
+                    EventContent::Moneyline(moneyline),
+                    AnswerEventContent::Moneyline { selection },
+                ) => {
+                    moneyline.answer = Some(selection);
+                    Ok((event.id, serde_json::to_value(event.contents).unwrap()))
+                }
+                (
+                    EventContent::OverUnder(over_under),
+                    AnswerEventContent::OverUnder { selection },
+                ) => {
+                    over_under.answer = Some(selection);
+                    Ok((event.id, serde_json::to_value(event.contents).unwrap()))
+                }
+                (
+                    EventContent::MultipleChoice(multiple_choice),
+                    AnswerEventContent::MultipleChoice { selection },
+                ) => {
+                    if selection != "unpicked" && !multiple_choice.options.contains(&selection) {
+                        return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                            .user_msg("Invalid multiple choice answer"));
+                    }
+                    multiple_choice.answer = Some(selection);
+                    Ok((event.id, serde_json::to_value(event.contents).unwrap()))
+                }
+                (EventContent::Survivor(survivor), AnswerEventContent::Survivor { winners }) => {
+                    if survivor.games.len() != winners.len() {
+                        return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                            .user_msg("Survivor Submissions do not cover all games"));
+                    }
+
+                    survivor
+                        .games
+                        .iter_mut()
+                        .zip(winners)
+                        .for_each(|(game, winner)| game.winner = Some(winner));
+
+                    Ok((event.id, serde_json::to_value(event.contents).unwrap()))
+                }
+                (EventContent::Numeric(numeric), AnswerEventContent::Numeric { answer }) => {
+                    numeric.answer = if answer.is_empty() {
+                        None
+                    } else {
+                        match answer.parse::<f64>() {
+                            Ok(answer) if answer.is_finite() => Some(answer),
+                            _ => {
+                                return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                                    .user_msg("Could not parse numeric answer"))
+                            }
+                        }
+                    };
+                    Ok((event.id, serde_json::to_value(event.contents).unwrap()))
+                }
                 _ => Err(RespErr::new(StatusCode::BAD_REQUEST)
                     .user_msg("Submitted event does not match its actual type")),
             }
@@ -131,8 +197,35 @@ pub async fn post(
             },
         )?;
 
+    let tiebreaker_answer = match tiebreaker_answer.as_deref() {
+        None | Some("") => None,
+        Some(answer) => Some(
+            answer
+                .parse::<f64>()
+                .ok()
+                .filter(|answer| answer.is_finite())
+                .ok_or(
+                    RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Could not parse tiebreaker answer"),
+                )?,
+        ),
+    };
+
     let mut transaction = pool.begin().await.map_err(AppError::from)?;
 
+    sqlx::query!(
+        "
+        UPDATE chapters
+        SET tiebreaker_answer = $1
+        WHERE id = $2
+        ",
+        tiebreaker_answer,
+        chapter.chapter_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
     sqlx::query!(
         r#"
         UPDATE events AS e
@@ -147,67 +240,25 @@ pub async fn post(
     .await
     .map_err(AppError::from)?;
 
-    sqlx::query!(
-        "
-        UPDATE PICKS
-        SET
-            POINTS = CALCULATIONS.POINTS_AWARDED
-        FROM
-            (
-                SELECT
-                    EVENT_ID,
-                    USER_ID,
-                    SUM(POINTS_AWARDED) AS POINTS_AWARDED
-                FROM
-                    (
-                        SELECT
-                            EVENT_ID,
-                            USER_ID,
-                            CASE
-                                WHEN CORRECT THEN WAGER
-                                ELSE 0
-                            END AS POINTS_AWARDED
-                        FROM
-                            (
-                                SELECT
-                                    E.ID AS EVENT_ID,
-                                    P.USER_ID,
-                                    JSONB_ARRAY_ELEMENTS(P.WAGER)::INT AS WAGER,
-                                    JSONB_ARRAY_ELEMENTS(E.CONTENTS -> 'spread_group') ->> 'answer' = JSONB_ARRAY_ELEMENTS(P.CHOICE) #>> '{}' AS CORRECT
-                                FROM
-                                    EVENTS AS E
-                                    JOIN PICKS AS P ON E.ID = P.EVENT_ID
-                                WHERE
-                                    E.EVENT_TYPE = 'spread_group'
-                                    AND E.CHAPTER_ID = $1
-                            ) as t2
-                    ) as t1
-                GROUP BY
-                    EVENT_ID,
-                    USER_ID
-                UNION
-                SELECT
-                    E.ID AS EVENT_ID,
-                    P.USER_ID,
-                    CASE
-                        WHEN E.CONTENTS -> 'user_input' -> 'acceptable_answers' @> P.CHOICE THEN P.WAGER::INTEGER
-                        ELSE 0
-                    END AS POINTS_AWARDED
-                FROM
-                    EVENTS AS E
-                    JOIN PICKS AS P ON E.ID = P.EVENT_ID
-                WHERE
-                    E.EVENT_TYPE = 'user_input'
-                    AND E.CHAPTER_ID = $1
-            ) AS CALCULATIONS
-        WHERE
-            PICKS.EVENT_ID = CALCULATIONS.EVENT_ID
-            AND PICKS.USER_ID = CALCULATIONS.USER_ID
-        ",
-        chapter.chapter_id
-    ).execute(&mut *transaction)
-    .await
-    .map_err(AppError::from)?;
+    crate::scoring::grade_chapter(chapter.chapter_id, &mut transaction)
+        .await
+        .map_err(AppError::from)?;
+
+    for (id, contents) in ids.iter().zip(&contents) {
+        if let Ok(EventContent::Survivor(survivor)) =
+            serde_json::from_value::<EventContent>(contents.clone())
+        {
+            apply_eliminations(
+                chapter.book_id,
+                chapter.chapter_id,
+                *id,
+                &survivor,
+                &mut transaction,
+            )
+            .await
+            .map_err(AppError::from)?;
+        }
+    }
 
     transaction.commit().await.map_err(AppError::from)?;
 
@@ -265,6 +316,38 @@ pub async fn visible(
     ))
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct EventToggleParams {
+    #[serde(rename(deserialize = "event-id"))]
+    event_id: i32,
+    toggle: bool,
+}
+
+pub async fn event_open(
+    State(state): State<AppStateRef>,
+    Extension(chapter): Extension<Chapter>,
+    Query(EventToggleParams { event_id, toggle }): Query<EventToggleParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    sqlx::query!(
+        r#"
+        UPDATE events
+        SET is_open = $1
+        WHERE id = $2 AND chapter_id = $3
+    "#,
+        toggle,
+        event_id,
+        chapter.chapter_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(crate::templates::chapter_admin::event_open_button(
+        event_id, toggle,
+    ))
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct UserInputParams {
     #[serde(rename(deserialize = "event-id"))]
@@ -305,6 +388,106 @@ pub async fn user_input(
     })
 }
 
+/// Copies the chapter's events, with answers cleared, into a new hidden and closed chapter
+pub async fn clone(
+    State(state): State<AppStateRef>,
+    Extension(chapter): Extension<Chapter>,
+) -> Result<impl IntoResponse, AppError<'static>> {
+    let mut transaction = state.pool.begin().await?;
+
+    let title = format!("{} (copy)", chapter.title)
+        .chars()
+        .take(30)
+        .collect::<String>();
+
+    let new_chapter_id = sqlx::query_scalar!(
+        "
+        INSERT INTO chapters (title, book_id, is_open, is_visible, tiebreaker, section, sort_order)
+        VALUES ($1, $2, FALSE, FALSE, $3, $4, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM chapters WHERE book_id = $2))
+        RETURNING id
+        ",
+        title,
+        chapter.book_id,
+        chapter.tiebreaker,
+        chapter.section
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    let events = get_events(chapter.chapter_id, &state.pool).await?;
+    let (event_types, event_contents): (Vec<_>, Vec<_>) = events
+        .into_iter()
+        .map(|event| {
+            (
+                event.event_type,
+                serde_json::to_value(event.contents.0.without_answers()).unwrap_or_default(),
+            )
+        })
+        .unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO events (book_id, chapter_id, event_type, contents)
+        SELECT $1 AS book_id, $2 AS chapter_id, event_type AS "event_type: EventType", contents
+        FROM UNNEST($3::event_types[], $4::jsonb[]) AS a(event_type, contents)
+        "#,
+        chapter.book_id,
+        new_chapter_id,
+        event_types as _,
+        &event_contents
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok([(
+        "HX-Redirect",
+        format!("/book/{}/chapter/{new_chapter_id}/admin/", chapter.book_id),
+    )])
+}
+
+#[derive(serde::Deserialize)]
+pub struct SaveTemplateForm {
+    name: String,
+}
+
+pub async fn save_template(
+    State(state): State<AppStateRef>,
+    Extension(chapter): Extension<Chapter>,
+    Form(SaveTemplateForm { name }): Form<SaveTemplateForm>,
+) -> Result<AppNotification, AppNotification> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Templates need a name".into(),
+        ));
+    }
+
+    let events = get_events(chapter.chapter_id, &state.pool)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|event| event.contents.0.without_answers())
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        "
+        INSERT INTO chapter_templates (book_id, name, events)
+        VALUES ($1, $2, $3)
+        ",
+        chapter.book_id,
+        name,
+        sqlx::types::Json(events) as _
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(AppNotification(StatusCode::OK, "Template saved".into()))
+}
+
 pub async fn delete(
     State(state): State<AppStateRef>,
     Extension(chapter): Extension<Chapter>,
@@ -346,6 +529,154 @@ pub async fn delete(
     Ok([("HX-Redirect", "../../..")].into_response())
 }
 
+type Teams = HashMap<i32, (String, Option<String>)>;
+
+fn team_name(teams: &Teams, team_id: i32) -> &str {
+    teams
+        .get(&team_id)
+        .map(|(name, _)| name.as_str())
+        .unwrap_or("Unknown team")
+}
+
+/// What a history entry was picked on. Events deleted since the pick have no contents
+fn event_label(contents: Option<&EventContent>, teams: &Teams) -> String {
+    match contents {
+        None => "Removed event".into(),
+        Some(EventContent::SpreadGroup(_)) => "Spread group".into(),
+        Some(EventContent::Moneyline(moneyline)) => format!(
+            "{} at {}",
+            team_name(teams, moneyline.away_id),
+            team_name(teams, moneyline.home_id)
+        ),
+        Some(EventContent::OverUnder(over_under)) => format!(
+            "{} at {} ({})",
+            team_name(teams, over_under.away_id),
+            team_name(teams, over_under.home_id),
+            over_under.total
+        ),
+        Some(EventContent::UserInput(user_input)) => user_input.title.clone(),
+        Some(EventContent::MultipleChoice(multiple_choice)) => multiple_choice.title.clone(),
+        Some(EventContent::Numeric(numeric)) => numeric.title.clone(),
+        Some(EventContent::Survivor(_)) => "Survivor".into(),
+    }
+}
+
+/// A pick's choice and wager as readable text, with home and away picks and survivor picks
+/// named by team. Spread groups list one choice per game
+fn pick_summary(
+    contents: Option<&EventContent>,
+    choice: &serde_json::Value,
+    wager: &serde_json::Value,
+    teams: &Teams,
+) -> String {
+    fn text(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    let side = |home_id: i32, away_id: i32, choice: &serde_json::Value| match choice.as_str() {
+        Some("home") => team_name(teams, home_id).to_string(),
+        Some("away") => team_name(teams, away_id).to_string(),
+        _ => text(choice),
+    };
+
+    match (contents, choice, wager) {
+        (
+            Some(EventContent::SpreadGroup(group)),
+            serde_json::Value::Array(choices),
+            serde_json::Value::Array(wagers),
+        ) => choices
+            .iter()
+            .zip(wagers)
+            .enumerate()
+            .map(|(i, (choice, wager))| {
+                let choice = match group.spreads.get(i) {
+                    Some(spread) => side(spread.home_id, spread.away_id, choice),
+                    None => text(choice),
+                };
+                format!("{} ({})", choice, text(wager))
+            })
+            .join(", "),
+        (Some(EventContent::Moneyline(moneyline)), choice, wager) => format!(
+            "{} ({})",
+            side(moneyline.home_id, moneyline.away_id, choice),
+            text(wager)
+        ),
+        (Some(EventContent::Survivor(_)), choice, wager) => {
+            let choice = match choice.as_i64().and_then(|id| i32::try_from(id).ok()) {
+                Some(team_id) => team_name(teams, team_id).to_string(),
+                None => text(choice),
+            };
+            format!("{} ({})", choice, text(wager))
+        }
+        (_, serde_json::Value::Array(choices), serde_json::Value::Array(wagers)) => choices
+            .iter()
+            .zip(wagers)
+            .map(|(choice, wager)| format!("{} ({})", text(choice), text(wager)))
+            .join(", "),
+        (_, choice, wager) => format!("{} ({})", text(choice), text(wager)),
+    }
+}
+
+/// Every pick submission in the chapter, newest first for each user
+pub async fn pick_history(
+    Extension(chapter): Extension<Chapter>,
+    State(state): State<AppStateRef>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+    let history = sqlx::query!(
+        r#"
+        SELECT
+            u.username,
+            h.event_id,
+            e.contents AS "contents?: sqlx::types::Json<EventContent>",
+            h.choice,
+            h.wager,
+            h.submitted_at
+        FROM pick_history AS h
+        JOIN users AS u ON h.user_id = u.id
+        LEFT JOIN events AS e ON h.event_id = e.id
+        WHERE h.chapter_id = $1
+        ORDER BY u.username, h.submitted_at DESC, h.event_id
+        "#,
+        chapter.chapter_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let teams = get_chapter_teams(chapter.chapter_id, pool).await?;
+
+    Ok(maud::html! {
+        div class="p-2 bg-white rounded-lg" {
+            @if history.is_empty() {
+                p { "No picks submitted yet" }
+            }
+            @for (username, submissions) in &history.iter().chunk_by(|record| &record.username) {
+                @let submissions = submissions.chunk_by(|record| record.submitted_at);
+                details class="my-1 text-left" {
+                    summary class="font-semibold" { (username) }
+                    @for (i, (submitted_at, picks)) in submissions.into_iter().enumerate() {
+                        div class="py-1 pl-3 text-sm border-t" {
+                            p {
+                                (crate::templates::local_time(submitted_at))
+                                @if i == 0 { span class="text-green-600" { " (current)" } }
+                            }
+                            ul class="pl-3" {
+                                @for pick in picks {
+                                    @let contents = pick.contents.as_ref().map(|contents| &contents.0);
+                                    li { (event_label(contents, &teams)) ": " (pick_summary(contents, &pick.choice, &pick.wager, &teams)) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
 pub async fn unsubmitted_users(
     Extension(chapter): Extension<Chapter>,
     State(state): State<AppStateRef>,
@@ -434,3 +765,119 @@ pub async fn unsubmitted_users(
         }
     })
 }
+
+#[derive(Debug, serde::Deserialize)]
+pub struct GuestSearchParams {
+    username: String,
+}
+
+/// Users who can't see this chapter yet: non-members, and guests of other chapters
+pub async fn guest_search(
+    State(state): State<AppStateRef>,
+    Extension(chapter): Extension<Chapter>,
+    Query(GuestSearchParams { username }): Query<GuestSearchParams>,
+) -> Result<maud::Markup, AppError<'static>> {
+    let pool = &state.pool;
+
+    if username.is_empty() {
+        return Ok(maud::html!());
+    }
+
+    let matching_users = sqlx::query!(
+        "
+        SELECT u.id, u.username
+        FROM users AS u
+        LEFT JOIN subscriptions AS s ON u.id = s.user_id AND s.book_id = $2
+        WHERE
+            LOWER(u.username) LIKE '%' || LOWER($1) || '%'
+            AND (
+                s.user_id IS NULL
+                OR NOT COALESCE((s.role -> 'guest' -> 'chapter_ids') @> TO_JSONB($3::INT), TRUE)
+            )
+        ",
+        username,
+        chapter.book_id,
+        chapter.chapter_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(maud::html!(
+        @for user in matching_users {
+            li {
+                button
+                    hx-post="invite-guest"
+                    hx-vals={r#"{"user_id":""#(user.id)r#""}"#}
+                    hx-on-click=r#"document.querySelector('input[type="search"]').value=""; this.closest('ul').innerHTML="";"# {
+                        (user.username)
+                    }
+            }
+        }
+    ))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct InviteGuestForm {
+    user_id: i32,
+}
+
+/// Adds a user to the book as a guest of this chapter, or extends an existing guest's chapters
+pub async fn invite_guest(
+    State(state): State<AppStateRef>,
+    Extension(chapter): Extension<Chapter>,
+    Form(InviteGuestForm { user_id }): Form<InviteGuestForm>,
+) -> Result<AppNotification, AppNotification> {
+    let pool = &state.pool;
+
+    let mut transaction = pool.begin().await.map_err(AppError::from)?;
+
+    let role = sqlx::query_scalar!(
+        r#"
+        SELECT role AS "role: sqlx::types::Json<BookRole>"
+        FROM subscriptions
+        WHERE user_id = $1 AND book_id = $2
+        FOR UPDATE
+        "#,
+        user_id,
+        chapter.book_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(AppError::from)?
+    .map(|role| role.0);
+
+    let chapter_ids = match role {
+        None => vec![chapter.chapter_id],
+        Some(BookRole::Guest { mut chapter_ids }) => {
+            if !chapter_ids.contains(&chapter.chapter_id) {
+                chapter_ids.push(chapter.chapter_id);
+            }
+            chapter_ids
+        }
+        Some(_) => {
+            return Err(AppNotification(
+                StatusCode::BAD_REQUEST,
+                "That user can already see this chapter".into(),
+            ))
+        }
+    };
+
+    sqlx::query!(
+        "
+        INSERT INTO subscriptions (user_id, book_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, book_id)
+        DO UPDATE SET role = EXCLUDED.role
+        ",
+        user_id,
+        chapter.book_id,
+        sqlx::types::Json(BookRole::Guest { chapter_ids }) as _
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    transaction.commit().await.map_err(AppError::from)?;
+
+    Ok(AppNotification(StatusCode::OK, "Guest Invited".into()))
+}
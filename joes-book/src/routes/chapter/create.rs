@@ -1,20 +1,29 @@
 use axum::{
-    extract::{Path, Query},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Extension, Json,
 };
 use axum_ctx::{RespErr, RespErrCtx, RespErrExt};
+use itertools::Itertools;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 use crate::{
     auth::AuthSession,
     db::{
-        book::BookSubscription,
+        book::{BookSubscription, BookType},
         event::{EventContent, EventType},
-        spread::Spread,
+        moneyline::Moneyline,
+        multiple_choice::MultipleChoice,
+        numeric::{Numeric, NumericScoring},
+        over_under::OverUnder,
+        spread::{Spread, SpreadGroup, WagerMode},
+        survivor::{Survivor, SurvivorGame},
+        team::get_teams,
+        template::{get_template, get_templates},
         user_input::UserInput,
     },
-    AppError, AppNotification,
+    AppError, AppNotification, AppStateRef,
 };
 
 pub async fn get(
@@ -22,18 +31,87 @@ pub async fn get(
     Extension(book_subscription): Extension<BookSubscription>,
 ) -> Result<maud::Markup, RespErr> {
     let username = auth_session.user.ok_or(AppError::BackendUser)?.username;
+    let templates = get_templates(book_subscription.id, &auth_session.backend.0)
+        .await
+        .map_err(AppError::from)?;
+
     Ok(crate::templates::chapter_create::markup(
         &username,
         &book_subscription.name,
+        book_subscription.kind,
+        &templates,
     ))
 }
 
+#[derive(serde::Deserialize)]
+pub struct TemplateParams {
+    id: i32,
+}
+
+/// The template's events as new, editable event forms
+pub async fn template(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(TemplateParams { id }): Query<TemplateParams>,
+) -> Result<maud::Markup, AppNotification> {
+    let pool = &state.pool;
+
+    let template = get_template(id, book_subscription.id, pool)
+        .await
+        .map_err(AppError::from)?
+        .ok_or(AppNotification(
+            StatusCode::NOT_FOUND,
+            "Could not find template".into(),
+        ))?;
+
+    let team_ids = template
+        .events
+        .iter()
+        .flat_map(EventContent::team_ids)
+        .unique()
+        .collect_vec();
+    let teams = get_teams(&team_ids, pool).await.map_err(AppError::from)?;
+
+    Ok(maud::html! {
+        @for event in template.events.iter() {
+            (crate::templates::add_event::from_template(event, &teams))
+        }
+    })
+}
+
+pub async fn delete_template(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Query(TemplateParams { id }): Query<TemplateParams>,
+) -> Result<maud::Markup, AppNotification> {
+    sqlx::query!(
+        "
+        DELETE FROM chapter_templates
+        WHERE id = $1 AND book_id = $2
+        ",
+        id,
+        book_subscription.id
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(maud::html!())
+}
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(tag = "type", rename_all(deserialize = "kebab-case"))]
 pub enum AddEventType {
     SpreadGroup,
     SpreadGroupAppend,
     UserInput,
+    Moneyline,
+    OverUnder,
+    MultipleChoice,
+    MultipleChoiceOption,
+    Numeric,
+    Survivor,
+    SurvivorAppend,
 }
 
 pub async fn add_event(Query(ty): Query<AddEventType>) -> maud::Markup {
@@ -64,6 +142,9 @@ pub struct SpreadSubmission {
     home_id: String,
     away_id: String,
     home_spread: String,
+    start_time: String,
+    #[serde(default)]
+    notes: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -75,26 +156,109 @@ pub struct SpreadSubmission {
 pub enum EventSubmissionType {
     SpreadGroup {
         spreads: Vec<SpreadSubmission>,
+        wager_mode: String,
+        bankroll: String,
     },
     UserInput {
         title: String,
         description: String,
         points: String,
     },
+    Moneyline {
+        home_id: String,
+        away_id: String,
+        points: String,
+    },
+    OverUnder {
+        home_id: String,
+        away_id: String,
+        total: String,
+        points: String,
+    },
+    MultipleChoice {
+        title: String,
+        description: String,
+        points: String,
+        options: Vec<String>,
+    },
+    Numeric {
+        title: String,
+        description: String,
+        points: String,
+        scoring: NumericScoring,
+    },
+    Survivor {
+        games: Vec<SurvivorGameSubmission>,
+    },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct SurvivorGameSubmission {
+    home_id: String,
+    away_id: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
 pub struct EventSubmissions {
-    #[serde(rename = "chapter-name")]
     chapter_name: String,
+    opens_at: String,
+    locks_at: String,
+    reveal_at: String,
+    tiebreaker: String,
+    #[serde(default)]
+    notes: String,
     events: Vec<EventSubmissionType>,
 }
 
-fn validate_events(events: Vec<EventSubmissionType>) -> Result<Vec<EventContent>, RespErr> {
+/// Times are submitted as RFC 3339 strings, with an empty string meaning unset
+pub fn parse_time(time: &str) -> Result<Option<OffsetDateTime>, RespErr> {
+    if time.is_empty() {
+        return Ok(None);
+    }
+
+    OffsetDateTime::parse(time, &Rfc3339)
+        .map(Some)
+        .ctx(StatusCode::BAD_REQUEST)
+        .user_msg("Could not parse time")
+}
+
+/// Answers the grader treats as placeholders rather than real outcomes
+const RESERVED_ANSWERS: [&str; 3] = ["unpicked", "push", "void"];
+
+/// Trims free text, treating blank text as unset
+pub fn optional_text(text: &str) -> Option<String> {
+    Some(text.trim())
+        .filter(|text| !text.is_empty())
+        .map(String::from)
+}
+
+pub fn validate_events(events: Vec<EventSubmissionType>) -> Result<Vec<EventContent>, RespErr> {
     let events = events
         .into_iter()
         .map(|curr_event| match curr_event {
-            EventSubmissionType::SpreadGroup { spreads } => {
+            EventSubmissionType::SpreadGroup {
+                spreads,
+                wager_mode,
+                bankroll,
+            } => {
+                let wager_mode = match wager_mode.as_str() {
+                    "confidence" => WagerMode::Confidence,
+                    "flat" => WagerMode::Flat,
+                    "bankroll" => match bankroll.parse() {
+                        Ok(total) if total > 0 => WagerMode::Bankroll { total },
+                        _ => {
+                            return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                                .user_msg("Bankroll must be a positive number of points"))
+                        }
+                    },
+                    _ => {
+                        return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                            .user_msg("Could not parse wager mode"))
+                    }
+                };
+
                 let spreads = spreads
                     .into_iter()
                     .map(
@@ -102,6 +266,8 @@ fn validate_events(events: Vec<EventSubmissionType>) -> Result<Vec<EventContent>
                              home_id,
                              away_id,
                              home_spread,
+                             start_time,
+                             notes,
                          }| {
                             let home_id = home_id
                                 .parse()
@@ -118,18 +284,24 @@ fn validate_events(events: Vec<EventSubmissionType>) -> Result<Vec<EventContent>
                                 }
                             };
 
+                            let start_time = parse_time(&start_time)?;
+
                             Ok(Spread {
                                 home_id,
                                 away_id,
                                 home_spread,
-                                notes: None,
+                                start_time,
+                                notes: optional_text(&notes),
                                 answer: None,
                             })
                         },
                     )
                     .collect::<Result<Vec<Spread>, RespErr>>()?;
 
-                Ok(EventContent::SpreadGroup(spreads))
+                Ok(EventContent::SpreadGroup(SpreadGroup {
+                    spreads,
+                    wager_mode,
+                }))
             }
             EventSubmissionType::UserInput {
                 title,
@@ -147,15 +319,182 @@ fn validate_events(events: Vec<EventSubmissionType>) -> Result<Vec<EventContent>
                     acceptable_answers: None,
                 }))
             }
+            EventSubmissionType::Moneyline {
+                home_id,
+                away_id,
+                points,
+            } => {
+                let home_id = home_id
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                let away_id = away_id
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                let points = points
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                Ok(EventContent::Moneyline(Moneyline {
+                    home_id,
+                    away_id,
+                    points,
+                    answer: None,
+                }))
+            }
+            EventSubmissionType::OverUnder {
+                home_id,
+                away_id,
+                total,
+                points,
+            } => {
+                let home_id = home_id
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                let away_id = away_id
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                let total = match total.parse() {
+                    Ok(a) if a % 0.5 == 0.0 => a,
+                    _ => {
+                        return Err(
+                            RespErr::new(StatusCode::BAD_REQUEST).user_msg("Could not parse total")
+                        )
+                    }
+                };
+                let points = points
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                Ok(EventContent::OverUnder(OverUnder {
+                    home_id,
+                    away_id,
+                    total,
+                    points,
+                    answer: None,
+                }))
+            }
+            EventSubmissionType::MultipleChoice {
+                title,
+                description,
+                points,
+                options,
+            } => {
+                let description = (!description.is_empty()).then_some(description);
+                let points = points
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                let options = options
+                    .into_iter()
+                    .map(|option| option.trim().to_string())
+                    .collect_vec();
+                if options.len() < 2 {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Multiple choice questions need at least two options"));
+                }
+                if options.iter().any(String::is_empty) || !options.iter().all_unique() {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Multiple choice options must be unique and non-empty"));
+                }
+                if options
+                    .iter()
+                    .any(|option| RESERVED_ANSWERS.contains(&option.as_str()))
+                {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(
+                        "Multiple choice options can't be named unpicked, push or void",
+                    ));
+                }
+                Ok(EventContent::MultipleChoice(MultipleChoice {
+                    title,
+                    description,
+                    points,
+                    options,
+                    answer: None,
+                }))
+            }
+            EventSubmissionType::Numeric {
+                title,
+                description,
+                points,
+                scoring,
+            } => {
+                let description = (!description.is_empty()).then_some(description);
+                let points = points
+                    .parse()
+                    .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                Ok(EventContent::Numeric(Numeric {
+                    title,
+                    description,
+                    points,
+                    scoring,
+                    answer: None,
+                }))
+            }
+            EventSubmissionType::Survivor { games } => {
+                let games = games
+                    .into_iter()
+                    .map(|SurvivorGameSubmission { home_id, away_id }| {
+                        let home_id = home_id
+                            .parse()
+                            .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                        let away_id = away_id
+                            .parse()
+                            .map_err(|_| RespErr::new(StatusCode::BAD_REQUEST))?;
+                        Ok(SurvivorGame {
+                            home_id,
+                            away_id,
+                            winner: None,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, RespErr>>()?;
+
+                let team_ids = games.iter().flat_map(|game| [game.home_id, game.away_id]);
+                if !team_ids.clone().all_unique() {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("A team can only play in one survivor game per chapter"));
+                }
+
+                Ok(EventContent::Survivor(Survivor { games }))
+            }
         })
         .collect::<Result<Vec<EventContent>, RespErr>>()?;
 
     Ok(events)
 }
 
+/// Survivor books hold exactly one set of survivor games per chapter, and nothing else
+pub fn validate_book_kind(kind: BookType, events: &[EventContent]) -> Result<(), AppNotification> {
+    let survivor_events = events
+        .iter()
+        .filter(|event| matches!(event, EventContent::Survivor(_)))
+        .count();
+    match kind {
+        BookType::Survivor if survivor_events != 1 || events.len() != 1 => Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Survivor chapters need exactly one set of survivor games".into(),
+        )),
+        BookType::Standard if survivor_events > 0 => Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Survivor games can only be added to survivor books".into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The event type column that matches an event's contents
+pub fn event_type(event: &EventContent) -> EventType {
+    match event {
+        EventContent::SpreadGroup(_) => EventType::SpreadGroup,
+        EventContent::UserInput(_) => EventType::UserInput,
+        EventContent::Moneyline(_) => EventType::Moneyline,
+        EventContent::OverUnder(_) => EventType::OverUnder,
+        EventContent::MultipleChoice(_) => EventType::MultipleChoice,
+        EventContent::Numeric(_) => EventType::Numeric,
+        EventContent::Survivor(_) => EventType::Survivor,
+    }
+}
+
 pub async fn post(
     auth_session: AuthSession,
     Path(book_id): Path<i32>,
+    Extension(book_subscription): Extension<BookSubscription>,
     Json(chapter_submission): Json<EventSubmissions>,
 ) -> Result<impl IntoResponse, AppNotification> {
     if chapter_submission.chapter_name.len() > 30 {
@@ -164,19 +503,40 @@ pub async fn post(
             "Chapter Name too long (> 30 chars)".into(),
         ));
     }
+    let opens_at = parse_time(&chapter_submission.opens_at)?;
+    let locks_at = parse_time(&chapter_submission.locks_at)?;
+    let reveal_at = parse_time(&chapter_submission.reveal_at)?;
+    if let (Some(opens_at), Some(locks_at)) = (opens_at, locks_at) {
+        if locks_at <= opens_at {
+            return Err(AppNotification(
+                StatusCode::BAD_REQUEST,
+                "Chapter must lock after it opens".into(),
+            ));
+        }
+    }
+
+    let tiebreaker = optional_text(&chapter_submission.tiebreaker);
+    let notes = optional_text(&chapter_submission.notes);
+
     let events = validate_events(chapter_submission.events)?;
+    validate_book_kind(book_subscription.kind, &events)?;
 
     let pool = auth_session.backend.0;
 
     let mut transaction = pool.begin().await.map_err(AppError::from)?;
 
     let record = sqlx::query!(
-        "INSERT INTO chapters (title, book_id, is_open)
-        VALUES ($1, $2, false)
+        "INSERT INTO chapters (title, book_id, is_open, opens_at, locks_at, reveal_at, tiebreaker, notes, sort_order)
+        VALUES ($1, $2, false, $3, $4, $5, $6, $7, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM chapters WHERE book_id = $2))
         RETURNING id
         ",
         chapter_submission.chapter_name,
-        book_id
+        book_id,
+        opens_at,
+        locks_at,
+        reveal_at,
+        tiebreaker,
+        notes
     )
     .fetch_one(&mut *transaction)
     .await
@@ -184,13 +544,7 @@ pub async fn post(
 
     let (event_types, event_contents): (Vec<_>, Vec<_>) = events
         .into_iter()
-        .map(|event| {
-            let event_type = match event {
-                EventContent::SpreadGroup(_) => EventType::SpreadGroup,
-                EventContent::UserInput(_) => EventType::UserInput,
-            };
-            (event_type, serde_json::to_value(event))
-        })
+        .map(|event| (event_type(&event), serde_json::to_value(event)))
         .unzip();
 
     let event_contents = event_contents
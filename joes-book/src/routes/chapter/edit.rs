@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum_ctx::{RespErr, RespErrCtx, RespErrExt, StatusCode};
+use itertools::Itertools;
+
+use crate::{
+    auth::AuthSession,
+    db::{
+        book::{BookSubscription, BookType},
+        chapter::Chapter,
+        event::{get_events, EventContent},
+        survivor::apply_eliminations,
+        team::get_chapter_teams,
+    },
+    AppError, AppNotification, AppStateRef,
+};
+
+use super::create::{
+    event_type, optional_text, validate_book_kind, validate_events, EventSubmissionType,
+};
+
+pub async fn get(
+    auth_session: AuthSession,
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Extension(chapter): Extension<Chapter>,
+) -> Result<maud::Markup, RespErr> {
+    let user = auth_session.user.ok_or(AppError::BackendUser)?;
+    let pool = &state.pool;
+
+    let events = get_events(chapter.chapter_id, pool)
+        .await
+        .map_err(AppError::from)?;
+
+    let teams = get_chapter_teams(chapter.chapter_id, pool)
+        .await
+        .map_err(AppError::from)?;
+
+    let pick_counts = pick_counts(chapter.chapter_id, pool)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(crate::templates::chapter_edit::markup(
+        &user.username,
+        &book_subscription,
+        &chapter,
+        &events,
+        &teams,
+        &pick_counts,
+    ))
+}
+
+async fn pick_counts(
+    chapter_id: i32,
+    pool: &sqlx::PgPool,
+) -> Result<HashMap<i32, i64>, sqlx::Error> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT event_id, COUNT(*) AS "count!"
+        FROM picks
+        WHERE chapter_id = $1
+        GROUP BY event_id
+        "#,
+        chapter_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|record| (record.event_id, record.count))
+    .collect())
+}
+
+/// What happens to picks on events whose contents changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PickPolicy {
+    #[default]
+    Keep,
+    Reset,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct EditSubmission {
+    chapter_name: String,
+    tiebreaker: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    pick_policy: PickPolicy,
+    #[serde(default)]
+    events: Vec<EditedEvent>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EditedEvent {
+    /// Empty for events added during this edit
+    #[serde(default, rename = "event-id")]
+    event_id: String,
+    #[serde(flatten)]
+    content: EventSubmissionType,
+}
+
+/// An event's setup with answers and notes stripped, so edits can be told apart from grading
+fn setup(content: &EventContent) -> serde_json::Value {
+    fn strip(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for key in ["answer", "acceptable_answers", "winner", "notes"] {
+                    map.remove(key);
+                }
+                map.values_mut().for_each(strip);
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(strip),
+            _ => (),
+        }
+    }
+
+    let mut value = serde_json::to_value(content).unwrap_or_default();
+    strip(&mut value);
+    value
+}
+
+/// `old`, answers included, with the game notes from `new`. Notes can change without
+/// affecting picks
+fn with_notes(mut old: EventContent, new: &EventContent) -> EventContent {
+    if let (EventContent::SpreadGroup(old), EventContent::SpreadGroup(new)) = (&mut old, new) {
+        old.spreads
+            .iter_mut()
+            .zip(&new.spreads)
+            .for_each(|(old, new)| old.notes.clone_from(&new.notes));
+    }
+    old
+}
+
+/// Whether picks made against `old` can still be read against `new`. Spread group picks line
+/// up with games by index and wagers depend on the wager mode, so those must stay the same
+fn picks_still_fit(old: &EventContent, new: &EventContent) -> bool {
+    match (old, new) {
+        (EventContent::SpreadGroup(old), EventContent::SpreadGroup(new)) => {
+            old.spreads.len() == new.spreads.len() && old.wager_mode == new.wager_mode
+        }
+        (old, new) => event_type(old) == event_type(new),
+    }
+}
+
+/// The choices a kept pick may still hold, for events whose options can be edited. Picks
+/// outside these are reset
+fn valid_choices(content: &EventContent) -> Option<serde_json::Value> {
+    match content {
+        EventContent::Survivor(survivor) => Some(
+            survivor
+                .games
+                .iter()
+                .flat_map(|game| [game.home_id, game.away_id])
+                .collect(),
+        ),
+        EventContent::MultipleChoice(multiple_choice) => {
+            Some(multiple_choice.options.iter().cloned().collect())
+        }
+        _ => None,
+    }
+}
+
+pub async fn post(
+    State(state): State<AppStateRef>,
+    Extension(book_subscription): Extension<BookSubscription>,
+    Extension(chapter): Extension<Chapter>,
+    Json(EditSubmission {
+        chapter_name,
+        tiebreaker,
+        notes,
+        pick_policy,
+        events: event_submissions,
+    }): Json<EditSubmission>,
+) -> Result<impl IntoResponse, AppNotification> {
+    let chapter_name = chapter_name.trim();
+    if chapter_name.is_empty() || chapter_name.len() > 30 {
+        return Err(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "Chapter name must be between 1 and 30 characters".into(),
+        ));
+    }
+
+    let tiebreaker = optional_text(&tiebreaker);
+    let notes = optional_text(&notes);
+
+    let (event_ids, submissions): (Vec<_>, Vec<_>) = event_submissions
+        .into_iter()
+        .map(|event| (event.event_id, event.content))
+        .unzip();
+
+    let event_ids = event_ids
+        .iter()
+        .map(|id| match id.as_str() {
+            "" => Ok(None),
+            id => id.parse::<i32>().map(Some),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .ctx(StatusCode::BAD_REQUEST)
+        .user_msg("Could not parse event id")?;
+
+    let contents = validate_events(submissions)?;
+    validate_book_kind(book_subscription.kind, &contents)?;
+
+    let pool = &state.pool;
+    let mut old_events = get_events(chapter.chapter_id, pool)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|event| (event.id, event))
+        .collect::<HashMap<_, _>>();
+
+    let mut inserted = Vec::new();
+    let mut updated = Vec::new();
+    let mut reset_picks = Vec::new();
+    let mut kept_choices = Vec::new();
+    for (event_id, content) in event_ids.into_iter().zip(contents) {
+        let Some(event_id) = event_id else {
+            inserted.push(content);
+            continue;
+        };
+
+        let old = old_events.remove(&event_id).ok_or(AppNotification(
+            StatusCode::BAD_REQUEST,
+            "An edited event does not belong to this chapter".into(),
+        ))?;
+
+        if setup(&old.contents.0) == setup(&content) {
+            updated.push((event_id, with_notes(old.contents.0, &content)));
+            continue;
+        }
+
+        if pick_policy == PickPolicy::Reset || !picks_still_fit(&old.contents.0, &content) {
+            reset_picks.push(event_id);
+        } else if let Some(choices) = valid_choices(&content) {
+            kept_choices.push((event_id, choices));
+        }
+        updated.push((event_id, content));
+    }
+    let removed = old_events.into_keys().collect_vec();
+
+    let mut transaction = pool.begin().await.map_err(AppError::from)?;
+
+    sqlx::query!(
+        "
+        UPDATE chapters
+        SET title = $1, tiebreaker = $2, notes = $3
+        WHERE id = $4
+        ",
+        chapter_name,
+        tiebreaker,
+        notes,
+        chapter.chapter_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    let cleared = removed.iter().chain(&reset_picks).copied().collect_vec();
+    sqlx::query!("DELETE FROM picks WHERE event_id = ANY($1)", &cleared)
+        .execute(&mut *transaction)
+        .await
+        .map_err(AppError::from)?;
+
+    let (kept_ids, kept_choices): (Vec<_>, Vec<_>) = kept_choices.into_iter().unzip();
+    sqlx::query!(
+        r#"
+        DELETE FROM picks AS p
+        USING UNNEST($1::INT[], $2::JSONB[]) AS v(event_id, choices)
+        WHERE p.event_id = v.event_id AND NOT v.choices @> JSONB_BUILD_ARRAY(p.choice)
+        "#,
+        &kept_ids,
+        &kept_choices
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    sqlx::query!("DELETE FROM events WHERE id = ANY($1)", &removed)
+        .execute(&mut *transaction)
+        .await
+        .map_err(AppError::from)?;
+
+    let (updated_ids, updated_types, updated_contents) = updated
+        .iter()
+        .map(|(id, content)| {
+            (
+                *id,
+                event_type(content),
+                serde_json::to_value(content).unwrap_or_default(),
+            )
+        })
+        .multiunzip::<(Vec<_>, Vec<_>, Vec<_>)>();
+
+    sqlx::query!(
+        r#"
+        UPDATE events AS e
+        SET event_type = c.event_type, contents = c.contents
+        FROM UNNEST($1::INT[], $2::event_types[], $3::JSONB[]) AS c(id, event_type, contents)
+        WHERE e.id = c.id
+        "#,
+        &updated_ids,
+        updated_types as _,
+        &updated_contents
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    let (inserted_types, inserted_contents): (Vec<_>, Vec<_>) = inserted
+        .iter()
+        .map(|content| {
+            (
+                event_type(content),
+                serde_json::to_value(content).unwrap_or_default(),
+            )
+        })
+        .unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO events (book_id, chapter_id, event_type, contents)
+        SELECT $1 AS book_id, $2 AS chapter_id, event_type AS "event_type: EventType", contents
+        FROM UNNEST($3::event_types[], $4::jsonb[]) AS a(event_type, contents)
+        "#,
+        chapter.book_id,
+        chapter.chapter_id,
+        inserted_types as _,
+        &inserted_contents
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    crate::scoring::grade_chapter(chapter.chapter_id, &mut transaction)
+        .await
+        .map_err(AppError::from)?;
+
+    // Picks may have been reset or removed, so eliminations from this chapter are recomputed
+    if book_subscription.kind == BookType::Survivor {
+        sqlx::query!(
+            "
+            UPDATE subscriptions
+            SET eliminated_in = NULL
+            WHERE book_id = $1 AND eliminated_in = $2
+            ",
+            chapter.book_id,
+            chapter.chapter_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(AppError::from)?;
+
+        let survivor_events = sqlx::query!(
+            r#"
+            SELECT id, contents AS "contents: sqlx::types::Json<EventContent>"
+            FROM events
+            WHERE chapter_id = $1 AND event_type = 'survivor'
+            "#,
+            chapter.chapter_id
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(AppError::from)?;
+
+        for event in survivor_events {
+            if let EventContent::Survivor(survivor) = &event.contents.0 {
+                apply_eliminations(
+                    chapter.book_id,
+                    chapter.chapter_id,
+                    event.id,
+                    survivor,
+                    &mut transaction,
+                )
+                .await
+                .map_err(AppError::from)?;
+            }
+        }
+    }
+
+    transaction.commit().await.map_err(AppError::from)?;
+
+    let admin_uri = format!(
+        "/book/{}/chapter/{}/admin/",
+        chapter.book_id, chapter.chapter_id
+    );
+
+    Ok([("HX-Redirect", admin_uri)])
+}
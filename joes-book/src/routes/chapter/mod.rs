@@ -1,5 +1,6 @@
 pub mod admin;
 pub mod create;
+pub mod edit;
 pub mod page;
 
 pub mod mw {
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
-use crate::db::book::BookRole;
-use crate::db::chapter::{get_chapter_users, ChapterUser};
+use crate::db::book::BookType;
+use crate::db::chapter::{get_chapter_users, get_tiebreaker, ChapterUser};
 use crate::db::event::{
     get_chapter_picks, get_events, get_picks, ChapterPick, ChapterPickHash, Event, EventContent,
+    Pick, UserPick,
 };
+use crate::db::spread::WagerMode;
+use crate::db::survivor::get_used_teams;
 use crate::db::team::get_chapter_teams;
 
 use crate::AppNotification;
@@ -28,23 +31,76 @@ pub async fn open_book(
 
     let user_picks = get_picks(user.id, chapter.chapter_id, &pool);
     let relevent_teams = get_chapter_teams(chapter.chapter_id, &pool);
+    let tiebreaker = get_tiebreaker(user.id, chapter.chapter_id, &pool);
 
     let user_picks = user_picks.await.map_err(AppError::from)?;
     let relevent_teams = relevent_teams.await.map_err(AppError::from)?;
+    let tiebreaker = tiebreaker.await.map_err(AppError::from)?;
+    let used_teams = if book_subscription.kind == BookType::Survivor {
+        other_chapter_teams(book_subscription.id, user.id, chapter.chapter_id, &pool).await?
+    } else {
+        HashMap::new()
+    };
+
+    let (open_picks, closed_picks): (Vec<_>, Vec<_>) =
+        user_picks.into_iter().partition(|(event, _)| event.is_open);
+
+    // Closed events are revealed alongside the events that can still be picked
+    let revealed = if closed_picks.is_empty() {
+        None
+    } else {
+        let closed_events = closed_picks
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect_vec();
+        let mut users = get_chapter_users(book_subscription.id, chapter.chapter_id, &pool).await?;
+        let picks = get_chapter_picks(chapter.chapter_id, &pool).await?;
+
+        // Tiebreaker guesses stay hidden until the whole chapter closes
+        users.iter_mut().for_each(|user| user.tiebreaker = None);
+
+        Some(maud::html! {
+            table class="m-1 overflow-auto picktable h-fit w-fit" {
+                (table_header(&closed_events, &relevent_teams))
+                (table_rows(&closed_events, &users, &picks, &relevent_teams))
+            }
+        })
+    };
 
     Ok(crate::templates::chapter_open::markup(
         &user.username,
-        &book_subscription.name,
+        book_subscription,
         chapter,
-        user_picks,
-        book_subscription.role == BookRole::Admin,
+        crate::templates::chapter_open::PickForm {
+            events: open_picks,
+            tiebreaker,
+            used_teams,
+        },
+        revealed,
         relevent_teams,
     ))
 }
 
+/// Survivor teams the user picked in chapters other than this one
+async fn other_chapter_teams(
+    book_id: i32,
+    user_id: i32,
+    chapter_id: i32,
+    pool: &sqlx::PgPool,
+) -> Result<HashMap<i32, String>, AppError<'static>> {
+    Ok(get_used_teams(book_id, user_id, pool)
+        .await?
+        .into_iter()
+        .filter(|(_, (used_chapter_id, _))| *used_chapter_id != chapter_id)
+        .map(|(team_id, (_, chapter_title))| (team_id, chapter_title))
+        .collect())
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PickSubmission {
     events: Vec<SubmissionEvent>,
+    #[serde(default)]
+    tiebreaker: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -62,17 +118,39 @@ pub enum SubmissionEvent {
         user_input: String,
         event_id: String,
     },
+    Moneyline {
+        event_id: String,
+        selection: String,
+    },
+    OverUnder {
+        event_id: String,
+        selection: String,
+    },
+    MultipleChoice {
+        event_id: String,
+        selection: String,
+    },
+    Numeric {
+        event_id: String,
+        guess: String,
+    },
+    Survivor {
+        event_id: String,
+        selection: String,
+    },
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SpreadGroupSpread {
-    num_points: String,
+    #[serde(default)]
+    num_points: Option<String>,
     selection: String,
 }
 
 pub async fn submit(
     auth_session: AuthSession,
+    Extension(book_subscription): Extension<BookSubscription>,
     Extension(chapter): Extension<Chapter>,
     picks: Result<Json<PickSubmission>, axum::extract::rejection::JsonRejection>,
 ) -> Result<AppNotification, AppNotification> {
@@ -87,7 +165,42 @@ pub async fn submit(
     let user_id = auth_session.user.ok_or(AppError::BackendUser)?.id;
     let pool = auth_session.backend.0;
 
-    let (event_ids, choices, wagers) = validate_picks(picks.events, &pool).await?;
+    let user_picks = get_picks(user_id, chapter.chapter_id, &pool)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|(event, pick)| (event.id, (event, pick)))
+        .collect::<HashMap<_, _>>();
+
+    let used_teams = if book_subscription.kind == BookType::Survivor {
+        if book_subscription.eliminated_in.is_some() {
+            return Err(AppNotification(
+                StatusCode::LOCKED,
+                "You have been eliminated from this pool".into(),
+            ));
+        }
+        other_chapter_teams(book_subscription.id, user_id, chapter.chapter_id, &pool).await?
+    } else {
+        HashMap::new()
+    };
+
+    let (event_ids, choices, wagers) = validate_picks(picks.events, &user_picks, &used_teams)?;
+
+    let tiebreaker = match (&chapter.tiebreaker, picks.tiebreaker) {
+        (Some(_), Some(guess)) => Some(
+            guess
+                .parse::<f64>()
+                .ok()
+                .filter(|guess| guess.is_finite())
+                .ok_or(AppNotification(
+                    StatusCode::BAD_REQUEST,
+                    "Could not parse tiebreaker".into(),
+                ))?,
+        ),
+        _ => None,
+    };
+
+    let mut transaction = pool.begin().await.map_err(AppError::from)?;
 
     sqlx::query!(
         r#"
@@ -106,38 +219,158 @@ pub async fn submit(
         &choices,
         &wagers
     )
-    .execute(&pool)
+    .execute(&mut *transaction)
     .await
     .map_err(AppError::from)?;
 
+    sqlx::query!(
+        r#"
+        INSERT INTO pick_history (book_id, chapter_id, user_id, event_id, choice, wager)
+        SELECT $1 AS book_id, $2 AS chapter_id, $3 AS user_id, event_id, choice, wager
+        FROM UNNEST($4::INT[], $5::JSONB[], $6::JSONB[]) AS a(event_id, choice, wager)
+        "#,
+        chapter.book_id,
+        chapter.chapter_id,
+        user_id,
+        &event_ids,
+        &choices,
+        &wagers
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(AppError::from)?;
+
+    if let Some(guess) = tiebreaker {
+        sqlx::query!(
+            "
+            INSERT INTO tiebreakers (chapter_id, user_id, guess)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (chapter_id, user_id)
+            DO UPDATE SET guess = EXCLUDED.guess
+            ",
+            chapter.chapter_id,
+            user_id,
+            guess
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(AppError::from)?;
+    }
+
+    transaction.commit().await.map_err(AppError::from)?;
+
     Ok(AppNotification(StatusCode::OK, "Picks Saved".into()))
 }
 
-async fn validate_picks(
+type ValidatedPicks = (Vec<i32>, Vec<serde_json::Value>, Vec<serde_json::Value>);
+
+fn validate_picks(
     events: Vec<SubmissionEvent>,
-    pool: &sqlx::PgPool,
-) -> Result<(Vec<i32>, Vec<serde_json::Value>, Vec<serde_json::Value>), RespErr> {
+    user_picks: &HashMap<i32, UserPick>,
+    used_teams: &HashMap<i32, String>,
+) -> Result<ValidatedPicks, RespErr> {
+    let now = time::OffsetDateTime::now_utc();
+
+    let find_event = |event_id: &str| {
+        let event_id = event_id
+            .parse::<i32>()
+            .ctx(StatusCode::BAD_REQUEST)
+            .user_msg("Could not parse event id")
+            .log_msg("Could not parse event id")?;
+
+        let (event, pick) = user_picks
+            .get(&event_id)
+            .ok_or(RespErr::new(StatusCode::BAD_REQUEST).user_msg("Event not found"))?;
+
+        if !event.is_open {
+            return Err(RespErr::new(StatusCode::LOCKED)
+                .user_msg("Picks cannot be changed for closed events"));
+        }
+
+        Ok((event, pick.as_ref()))
+    };
+
     let (events, choices, wagers) = events
         .into_iter()
         .map(|event| match event {
             SubmissionEvent::SpreadGroup { event_id, spreads } => {
-                let (min_points, max_points) = (1, spreads.len() as i32);
+                let (event, pick) = find_event(&event_id)?;
+                let EventContent::SpreadGroup(ref group) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                let event_spreads = &group.spreads;
+                if event_spreads.len() != spreads.len() {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Spread Group Submissions do not cover all games"));
+                }
+
+                let (prev_choices, prev_wagers) = match pick {
+                    Some(Pick {
+                        choice: serde_json::Value::Array(choice),
+                        wager: serde_json::Value::Array(wager),
+                        ..
+                    }) => (choice.as_slice(), wager.as_slice()),
+                    _ => (&[][..], &[][..]),
+                };
+
+                let (min_points, max_points) = match group.wager_mode {
+                    WagerMode::Confidence => (1, spreads.len() as i32),
+                    WagerMode::Flat => (1, 1),
+                    WagerMode::Bankroll { total } => (0, total),
+                };
                 let mut point_choices = vec![0; spreads.len()];
+                let mut points_used = 0;
                 let (choices, wagers) =
                         spreads
                             .into_iter()
-                            .map(|spread| {
-                                let amount = spread
-                                            .num_points
-                                            .parse::<i32>()
-                                            .ctx(StatusCode::BAD_REQUEST)
-                                            .user_msg("Could not parse Spread Group Points")?;
-
-                                if amount < min_points || amount > max_points {
+                            .zip(event_spreads)
+                            .enumerate()
+                            .map(|(i, (spread, event_spread))| {
+                                let started = event_spread.has_started(now);
+                                let prev_choice = prev_choices
+                                    .get(i)
+                                    .and_then(serde_json::Value::as_str)
+                                    .filter(|choice| *choice != "unpicked");
+                                // Games that started without a pick wager nothing, so they don't
+                                // use up a confidence value or any of the bankroll
+                                let missed = started && prev_choice.is_none();
+
+                                let amount = match group.wager_mode {
+                                    _ if missed => 0,
+                                    WagerMode::Flat => 1,
+                                    WagerMode::Confidence | WagerMode::Bankroll { .. } => spread
+                                        .num_points
+                                        .unwrap_or_default()
+                                        .parse::<i32>()
+                                        .ctx(StatusCode::BAD_REQUEST)
+                                        .user_msg("Could not parse Spread Group Points")?,
+                                };
+
+                                if !missed && (amount < min_points || amount > max_points) {
                                     return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(format!("Points must be in range {min_points}-{max_points}")))
                                 }
 
-                                point_choices[amount as usize - 1] += 1;
+                                if started {
+                                    // Started games keep whatever was picked before kickoff
+                                    let unchanged = match prev_choice {
+                                        Some(prev_choice) => {
+                                            spread.selection == prev_choice
+                                                && prev_wagers.get(i).and_then(serde_json::Value::as_i64) == Some(amount.into())
+                                        }
+                                        None => spread.selection == "unpicked",
+                                    };
+                                    if !unchanged {
+                                        return Err(RespErr::new(StatusCode::LOCKED).user_msg("Picks cannot be changed for games that have already started"))
+                                    }
+                                } else if spread.selection != "home" && spread.selection != "away" {
+                                    return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg("Could not parse Spread Group Selection"))
+                                }
+
+                                if group.wager_mode == WagerMode::Confidence && !missed {
+                                    point_choices[amount as usize - 1] += 1;
+                                }
+                                points_used += amount;
                                 Ok((
                                     serde_json::Value::String(spread.selection),
                                     serde_json::Value::Number(amount.into()),
@@ -156,13 +389,21 @@ async fn validate_picks(
                                     Ok::<_, RespErr>((choices, wagers))
                                 },
                             )?;
-                    let double_used = point_choices.iter().enumerate().filter_map(|(i, count)| (*count > 1).then_some(i+1)).join(",");
-                    if !double_used.is_empty() {
-                        let unused = point_choices.iter().enumerate().filter_map(|(i, count)| (*count < 1).then_some(i+1)).join(",");
-                        return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(format!("Points Used<br/>Multiple Times: {double_used}<br/>Point(s) available: {unused}")))
+                match group.wager_mode {
+                    WagerMode::Confidence => {
+                        let double_used = point_choices.iter().enumerate().filter_map(|(i, count)| (*count > 1).then_some(i+1)).join(",");
+                        if !double_used.is_empty() {
+                            let unused = point_choices.iter().enumerate().filter_map(|(i, count)| (*count < 1).then_some(i+1)).join(",");
+                            return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(format!("Points Used<br/>Multiple Times: {double_used}<br/>Point(s) available: {unused}")))
+                        }
                     }
+                    WagerMode::Bankroll { total } if points_used > total => {
+                        return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg(format!("Wagers use {points_used} points but the bankroll is only {total}")))
+                    }
+                    WagerMode::Bankroll { .. } | WagerMode::Flat => (),
+                }
                 Ok((
-                    event_id,
+                    event.id,
                     serde_json::Value::Array(choices),
                     serde_json::Value::Array(wagers),
                 ))
@@ -170,17 +411,128 @@ async fn validate_picks(
             SubmissionEvent::UserInput {
                 user_input,
                 event_id,
-            } => Ok((
+            } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::UserInput(_) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                Ok((
+                    event.id,
+                    serde_json::Value::String(user_input),
+                    serde_json::Value::Number(1.into()),
+                ))
+            }
+            SubmissionEvent::Moneyline {
+                event_id,
+                selection,
+            } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::Moneyline(ref moneyline) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                if selection != "home" && selection != "away" {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Could not parse Moneyline Selection"));
+                }
+                Ok((
+                    event.id,
+                    serde_json::Value::String(selection),
+                    serde_json::Value::Number(moneyline.points.into()),
+                ))
+            }
+            SubmissionEvent::OverUnder {
+                event_id,
+                selection,
+            } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::OverUnder(ref over_under) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                if selection != "over" && selection != "under" {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Could not parse Over/Under Selection"));
+                }
+                Ok((
+                    event.id,
+                    serde_json::Value::String(selection),
+                    serde_json::Value::Number(over_under.points.into()),
+                ))
+            }
+            SubmissionEvent::MultipleChoice {
+                event_id,
+                selection,
+            } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::MultipleChoice(ref multiple_choice) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                if !multiple_choice.options.contains(&selection) {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Invalid multiple choice selection"));
+                }
+                Ok((
+                    event.id,
+                    serde_json::Value::String(selection),
+                    serde_json::Value::Number(multiple_choice.points.into()),
+                ))
+            }
+            SubmissionEvent::Survivor {
                 event_id,
-                serde_json::Value::String(user_input),
-                serde_json::Value::Number(1.into()),
-            )),
+                selection,
+            } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::Survivor(ref survivor) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                let team_id = selection
+                    .parse::<i32>()
+                    .ctx(StatusCode::BAD_REQUEST)
+                    .user_msg("Could not parse Survivor Selection")?;
+                if survivor.game_for(team_id).is_none() {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("That team is not playing in this chapter"));
+                }
+                if used_teams.contains_key(&team_id) {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("You already used that team in an earlier chapter"));
+                }
+                Ok((
+                    event.id,
+                    serde_json::Value::Number(team_id.into()),
+                    serde_json::Value::Number(0.into()),
+                ))
+            }
+            SubmissionEvent::Numeric { event_id, guess } => {
+                let (event, _) = find_event(&event_id)?;
+                let EventContent::Numeric(ref numeric) = event.contents.0 else {
+                    return Err(RespErr::new(StatusCode::BAD_REQUEST)
+                        .user_msg("Submitted event does not match its actual type"));
+                };
+                let guess = guess
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .ok_or(
+                        RespErr::new(StatusCode::BAD_REQUEST)
+                            .user_msg("Could not parse numeric guess"),
+                    )?;
+                Ok((
+                    event.id,
+                    serde_json::Value::Number(guess),
+                    serde_json::Value::Number(numeric.points.into()),
+                ))
+            }
         })
         .try_fold(
             (Vec::new(), Vec::new(), Vec::new()),
             |(mut events, mut choices, mut wagers),
              curr_item: Result<
-                (String, serde_json::Value, serde_json::Value),
+                (i32, serde_json::Value, serde_json::Value),
                 RespErr,
             >| {
                 let (curr_event, curr_choices, curr_wager) = curr_item?;
@@ -191,32 +543,7 @@ async fn validate_picks(
             },
         )?;
 
-    let event_ids = events
-        .iter()
-        .map(|event| event.parse::<i32>())
-        .collect::<Result<Vec<_>, _>>()
-        .ctx(StatusCode::BAD_REQUEST)
-        .user_msg("Could not parse event id")
-        .log_msg("Could not parse event id")?;
-
-    let unknown_events = sqlx::query!(
-        r#"
-        SELECT a
-        FROM UNNEST($1::INT[]) AS a
-        LEFT JOIN events on a = events.id
-        WHERE events.id IS NULL
-    "#,
-        &event_ids
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(AppError::from)?;
-
-    if !unknown_events.is_empty() {
-        return Err(RespErr::new(StatusCode::BAD_REQUEST).user_msg("Event not found"));
-    }
-
-    Ok((event_ids, choices, wagers))
+    Ok((events, choices, wagers))
 }
 
 pub async fn closed_book(
@@ -257,7 +584,7 @@ pub async fn closed_book(
         }),
         Some(maud::html! {
             div class="flex flex-col flex-grow overflow-scroll border border-black" {
-                @if book_subscription.role == BookRole::Admin {
+                @if book_subscription.role.is_admin() {
                     div class="flex justify-center" {
                         fieldset class="w-1/2 border border-orange-600 max-w-60" {
                             legend class="ml-3" { "Admin Section" }
@@ -270,6 +597,17 @@ pub async fn closed_book(
                     }
 
                 }
+                @if let Some(notes) = &chapter.notes {
+                    div class="m-1" { (crate::templates::markdown(notes)) }
+                }
+                @if let Some(tiebreaker) = &chapter.tiebreaker {
+                    p class="m-1" {
+                        "Tiebreaker: " (tiebreaker)
+                        @if let Some(answer) = chapter.tiebreaker_answer {
+                            " (Actual: " (answer) ")"
+                        }
+                    }
+                }
                 table class="m-1 overflow-auto picktable h-fit w-fit" {
                     (table_header(&events, &relevent_teams))
                     (table_rows(&events, &users, &user_picks, &relevent_teams))
@@ -290,19 +628,52 @@ fn table_header(
             @for event in events {
                 @match &event.contents.0 {
                     EventContent::SpreadGroup(group) => {
-                        @for spread in group {
+                        @for spread in &group.spreads {
                             th {
                                 p { (relevent_teams[&spread.away_id].0) " " (format!("({:+})", -1. * spread.home_spread)) " at " (relevent_teams[&spread.home_id].0)}
+                                @if let Some(notes) = &spread.notes {
+                                    div class="text-xs font-normal" { (crate::templates::markdown(notes)) }
+                                }
                             }
                         }
                     },
                     EventContent::UserInput(input) => { th { p { (input.title) } } }
+                    EventContent::Moneyline(moneyline) => {
+                        th {
+                            p { (relevent_teams[&moneyline.away_id].0) " at " (relevent_teams[&moneyline.home_id].0) }
+                        }
+                    }
+                    EventContent::MultipleChoice(multiple_choice) => { th { p { (multiple_choice.title) } } }
+                    EventContent::Numeric(numeric) => { th { p { (numeric.title) } } }
+                    EventContent::Survivor(_) => { th { p { "Survivor" } } }
+                    EventContent::OverUnder(over_under) => {
+                        th {
+                            p { (relevent_teams[&over_under.away_id].0) " at " (relevent_teams[&over_under.home_id].0) " (O/U " (over_under.total) ")" }
+                        }
+                    }
                 }
             }
         }
     )
 }
 
+/// Cell color for a graded pick, as stored by the grader
+fn correct_color(correct: Option<bool>) -> &'static str {
+    match correct {
+        Some(true) => "bg-green-300",
+        Some(false) => "bg-red-300",
+        None => "",
+    }
+}
+
+/// A team's name, tolerating picks that name a team no longer in the chapter
+fn team_name(teams: &HashMap<i32, (String, Option<String>)>, team_id: i32) -> &str {
+    teams
+        .get(&team_id)
+        .map(|(name, _)| name.as_str())
+        .unwrap_or("Unknown team")
+}
+
 fn table_rows(
     events: &[Event],
     users: &[ChapterUser],
@@ -312,51 +683,57 @@ fn table_rows(
     maud::html!(
         tbody {
             // Each user
-            @for ChapterUser { user_id, username, total_points } in users {
+            @for ChapterUser { user_id, username, total_points, tiebreaker } in users {
                 tr {
                     td {
                         p {(username)}
                         p {(total_points) " point" (if *total_points != 1 {"s"} else {""})}
+                        @if let Some(tiebreaker) = tiebreaker {
+                            p class="text-xs" {"Tiebreaker: " (tiebreaker)}
+                        }
                     }
                     // Each event
                     @for event in events {
                         // Event type
                         @match (&event.contents.0, picks_by_user.get(&ChapterPickHash{event_id: event.id, user_id: *user_id})) {
-                            (EventContent::SpreadGroup(spreads), Some(ChapterPick::SpreadGroup { choice, wager, .. })) => {
-                                @for (i, spread) in spreads.iter().enumerate() {
-                                    @let bg_color = match spread.answer.as_ref().map(|a| *a == choice[i]) {
-                                        _ if spread.answer.as_ref().map(|a| *a == "push").unwrap_or(false) => "bg-orange-300",
-                                        _ if spread.answer.as_ref().map(|a| *a == "unpicked").unwrap_or(false) => "",
-                                        Some(true) => "bg-green-300",
-                                        Some(false) => "bg-red-300",
-                                        None => "bg-grey-300"
+                            (EventContent::SpreadGroup(group), Some(ChapterPick::SpreadGroup { choice, wager, correct, .. })) => {
+                                @for (i, spread) in group.spreads.iter().enumerate() {
+                                    @let bg_color = match (spread.answer.as_deref(), correct.as_ref().and_then(|correct| correct.get(i).copied().flatten())) {
+                                        (Some("push"), _) => "bg-orange-300",
+                                        (Some("void"), _) => "bg-gray-300",
+                                        (_, Some(true)) => "bg-green-300",
+                                        (_, Some(false)) => "bg-red-300",
+                                        (_, None) => "",
                                     };
 
-                                    @let team_id = match choice[i].as_str() {
-                                        "home" => spread.home_id,
-                                        "away" => spread.away_id,
-                                        _ => panic!()
+                                    // Stored picks may not line up with the games, so missing entries show as unpicked
+                                    @let team_id = match choice.get(i).map(String::as_str) {
+                                        Some("home") => Some(spread.home_id),
+                                        Some("away") => Some(spread.away_id),
+                                        _ => None
                                     };
 
                                     td class={(bg_color)} {
-                                        p {(relevent_teams[&team_id].0)}
-                                        p {(wager[i])}
+                                        @if let Some(team_id) = team_id {
+                                            p {(team_name(relevent_teams, team_id))}
+                                        } @else {
+                                            p class="text-red-500" {"No Pick"}
+                                        }
+                                        @if let Some(wager) = wager.get(i) {
+                                            p {(wager)}
+                                        }
                                     }
                                 }
                             },
-                            (EventContent::SpreadGroup(spreads), None) => {
-                                @for _ in spreads {
+                            (EventContent::SpreadGroup(group), None) => {
+                                @for _ in &group.spreads {
                                     td {
                                         p class="text-red-500" {"No Pick"}
                                     }
                                 }
                             },
-                            (EventContent::UserInput(_), Some(ChapterPick::UserInput { choice, wager, points })) => {
-                                @let bg_color = match points.as_ref().map(|p| p == wager) {
-                                    Some(true) => "bg-green-300",
-                                    Some(false) => "bg-red-300",
-                                    None => ""
-                                };
+                            (EventContent::UserInput(_), Some(ChapterPick::UserInput { choice, wager, correct, .. })) => {
+                                @let bg_color = correct_color(*correct);
 
                                 td class={(bg_color)} {
                                     p {(choice)}
@@ -368,6 +745,79 @@ fn table_rows(
                                     p class="text-red-500" {"No Pick"}
                                 }
                             }
+                            (EventContent::Moneyline(moneyline), Some(ChapterPick::Moneyline { choice, wager, correct, .. })) => {
+                                @let bg_color = correct_color(*correct);
+
+                                @let team_id = if choice == "home" { moneyline.home_id } else { moneyline.away_id };
+
+                                td class={(bg_color)} {
+                                    p {(team_name(relevent_teams, team_id))}
+                                    p {(wager)}
+                                }
+                            }
+                            (EventContent::Moneyline(_), None) => {
+                                td {
+                                    p class="text-red-500" {"No Pick"}
+                                }
+                            }
+                            (EventContent::OverUnder(over_under), Some(ChapterPick::OverUnder { choice, wager, correct, .. })) => {
+                                @let bg_color = match over_under.answer.as_deref() {
+                                    Some("push") => "bg-orange-300",
+                                    Some("void") => "bg-gray-300",
+                                    _ => correct_color(*correct),
+                                };
+
+                                td class={(bg_color)} {
+                                    p { @if choice == "over" { "Over" } @else { "Under" } }
+                                    p {(wager)}
+                                }
+                            }
+                            (EventContent::OverUnder(_), None) => {
+                                td {
+                                    p class="text-red-500" {"No Pick"}
+                                }
+                            }
+                            (EventContent::MultipleChoice(_), Some(ChapterPick::MultipleChoice { choice, wager, correct, .. })) => {
+                                @let bg_color = correct_color(*correct);
+
+                                td class={(bg_color)} {
+                                    p {(choice)}
+                                    p {(wager)}
+                                }
+                            }
+                            (EventContent::MultipleChoice(_), None) => {
+                                td {
+                                    p class="text-red-500" {"No Pick"}
+                                }
+                            }
+                            (EventContent::Numeric(numeric), Some(ChapterPick::Numeric { choice, points, correct, .. })) => {
+                                @let bg_color = correct_color(*correct);
+
+                                td class={(bg_color)} {
+                                    p {(choice)}
+                                    @if let Some(answer) = numeric.answer {
+                                        p { "Actual: " (answer) }
+                                    }
+                                    p {(points.unwrap_or_default())}
+                                }
+                            }
+                            (EventContent::Survivor(_), Some(ChapterPick::Survivor { choice, correct, .. })) => {
+                                @let bg_color = correct_color(*correct);
+
+                                td class={(bg_color)} {
+                                    p {(team_name(relevent_teams, *choice))}
+                                }
+                            }
+                            (EventContent::Survivor(_), None) => {
+                                td {
+                                    p class="text-red-500" {"No Pick"}
+                                }
+                            }
+                            (EventContent::Numeric(_), None) => {
+                                td {
+                                    p class="text-red-500" {"No Pick"}
+                                }
+                            }
                             _ => {
                                 p { "Something Went Wrong!!!" }
                             }
@@ -6,7 +6,12 @@ use axum::{
 use axum_ctx::RespErr;
 use reqwest::StatusCode;
 
-use crate::{auth::AuthSession, templates::base, AppError, AppNotification};
+use crate::{
+    auth::AuthSession,
+    db::invite::{redeem_invite, INVITE_COOKIE},
+    templates::base,
+    AppError, AppNotification,
+};
 
 use super::session::OauthProfile;
 
@@ -200,6 +205,13 @@ pub async fn post(
     .await
     .map_err(AppError::from)?;
 
+    let invited_book = match cookie_jar.get(INVITE_COOKIE) {
+        Some(invite_token) => redeem_invite(invite_token.value(), user.id, &mut transaction)
+            .await
+            .map_err(AppError::from)?,
+        None => None,
+    };
+
     auth_session
         .login(&user)
         .await
@@ -207,5 +219,13 @@ pub async fn post(
 
     transaction.commit().await.map_err(AppError::from)?;
 
-    Ok((cookie_jar.remove("signup_token"), [("HX-Location", "/")]).into_response())
+    let location = invited_book.map_or("/".to_string(), |book_id| format!("/book/{book_id}/"));
+
+    Ok((
+        cookie_jar
+            .remove("signup_token")
+            .remove(tower_sessions::cookie::Cookie::build(INVITE_COOKIE).path("/")),
+        [("HX-Location", location)],
+    )
+        .into_response())
 }
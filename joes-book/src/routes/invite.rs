@@ -0,0 +1,60 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    auth::{AuthSession, BackendPgDB},
+    db::invite::{redeem_invite, INVITE_COOKIE},
+    templates::base,
+    AppError,
+};
+
+pub async fn redeem(
+    auth_session: AuthSession,
+    cookie_jar: CookieJar,
+    Path(token): Path<String>,
+) -> Result<Response, AppError<'static>> {
+    let Some(user) = auth_session.user else {
+        // Hold on to the invite until they have logged in or finished signing up
+        let cookie = tower_sessions::cookie::Cookie::build((INVITE_COOKIE, token))
+            .http_only(true)
+            .same_site(tower_sessions::cookie::SameSite::Lax)
+            .path("/")
+            .build();
+
+        return Ok((cookie_jar.add(cookie), Redirect::to("/login")).into_response());
+    };
+    let BackendPgDB(pool) = auth_session.backend;
+
+    let mut transaction = pool.begin().await?;
+    let book_id = redeem_invite(&token, user.id, &mut transaction).await?;
+    transaction.commit().await?;
+
+    let cookie_jar =
+        cookie_jar.remove(tower_sessions::cookie::Cookie::build(INVITE_COOKIE).path("/"));
+
+    let Some(book_id) = book_id else {
+        return Ok((
+            cookie_jar,
+            base(
+                Some("Invite Expired"),
+                None,
+                None,
+                None,
+                Some(maud::html! {
+                    div class="flex flex-col items-center justify-center pt-10" {
+                        p { "This invite link has expired or has already been used up." }
+                        p { "Ask the book's admin for a new one." }
+                        a href="/" class="text-blue-400 hover:underline" { "Home" }
+                    }
+                }),
+                None,
+            ),
+        )
+            .into_response());
+    };
+
+    Ok((cookie_jar, Redirect::to(&format!("/book/{book_id}/"))).into_response())
+}
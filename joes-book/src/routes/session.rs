@@ -278,6 +278,10 @@ pub mod google {
                 .await
                 .ctx(StatusCode::INTERNAL_SERVER_ERROR)
                 .log_msg("Could not log in via google oauth")?;
+
+            if let Some(invite_token) = cookie_jar.get(crate::db::invite::INVITE_COOKIE) {
+                return Err(Redirect::to(&format!("/invite/{}", invite_token.value())).into());
+            }
             return Err(Redirect::to("/").into());
         }
 
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::db::chapter::apply_schedules;
+
+const TICK: Duration = Duration::from_secs(15);
+
+/// Opens, locks and reveals chapters on their scheduled times
+pub fn spawn(pool: PgPool) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            if let Err(e) = apply_schedules(&pool).await {
+                tracing::error!("Could not apply chapter schedules: {e}");
+            }
+        }
+    })
+}
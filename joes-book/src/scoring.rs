@@ -0,0 +1,634 @@
+use itertools::Itertools;
+use serde_json::json;
+use sqlx::{types::Json, Postgres, Transaction};
+
+use crate::db::{
+    book::PushRule,
+    event::{Event, EventContent, EventType, Pick},
+    moneyline::Moneyline,
+    multiple_choice::MultipleChoice,
+    numeric::{Numeric, NumericScoring},
+    over_under::OverUnder,
+    spread::SpreadGroup,
+    survivor::Survivor,
+    user_input::UserInput,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickScore {
+    pub pick_id: i32,
+    pub points: i32,
+    /// Mirrors the shape of the pick's choice: one entry per game for spread groups, a single
+    /// value otherwise. `null` means the pick couldn't be judged yet, or the game was a push or void
+    pub correct: serde_json::Value,
+}
+
+/// Scores every pick made on a single event. Picks that can't be read, or that are on an
+/// event without an answer yet, are worth zero points
+pub fn score_event(event: &EventContent, picks: &[Pick], push_rule: PushRule) -> Vec<PickScore> {
+    let score = |pick: &Pick| match event {
+        EventContent::SpreadGroup(group) => {
+            let (points, correct) = spread_group_points(group, pick, push_rule);
+            (points, json!(correct))
+        }
+        EventContent::UserInput(input) => single(user_input_points(input, pick)),
+        EventContent::Moneyline(moneyline) => single(moneyline_points(moneyline, pick)),
+        EventContent::OverUnder(over_under) => {
+            single(over_under_points(over_under, pick, push_rule))
+        }
+        EventContent::MultipleChoice(multiple_choice) => {
+            single(multiple_choice_points(multiple_choice, pick))
+        }
+        EventContent::Numeric(numeric) => single(numeric_points(numeric, pick, picks)),
+        EventContent::Survivor(survivor) => single(survivor_points(survivor, pick)),
+    };
+
+    picks
+        .iter()
+        .map(|pick| {
+            let (points, correct) = score(pick);
+            PickScore {
+                pick_id: pick.id,
+                points,
+                correct,
+            }
+        })
+        .collect()
+}
+
+fn single((points, correct): (i32, Option<bool>)) -> (i32, serde_json::Value) {
+    (points, json!(correct))
+}
+
+fn wager(pick: &Pick) -> i32 {
+    pick.wager.as_i64().unwrap_or_default() as i32
+}
+
+fn choice(pick: &Pick) -> Option<&str> {
+    pick.choice.as_str()
+}
+
+fn push_points(wager: i32, push_rule: PushRule) -> i32 {
+    match push_rule {
+        PushRule::Zero => 0,
+        PushRule::Refund => wager,
+        PushRule::Half => wager / 2,
+    }
+}
+
+/// Awards the wager when a two sided pick matches the answer. Pushes score by the book's rule,
+/// anything else (void, unpicked, ungraded) is worth nothing and is neither right nor wrong
+fn two_sided(
+    choice: Option<&str>,
+    sides: [&str; 2],
+    answer: Option<&str>,
+    wager: i32,
+    push_rule: PushRule,
+) -> (i32, Option<bool>) {
+    match (choice, answer) {
+        (Some(choice), Some("push")) if sides.contains(&choice) => {
+            (push_points(wager, push_rule), None)
+        }
+        (Some(choice), Some(answer)) if sides.contains(&choice) && sides.contains(&answer) => {
+            if choice == answer {
+                (wager, Some(true))
+            } else {
+                (0, Some(false))
+            }
+        }
+        _ => (0, None),
+    }
+}
+
+/// Sums the wager for each game picked correctly. Choices and wagers line up with the group's spreads by index
+fn spread_group_points(
+    group: &SpreadGroup,
+    pick: &Pick,
+    push_rule: PushRule,
+) -> (i32, Vec<Option<bool>>) {
+    let (Some(choices), Some(wagers)) = (pick.choice.as_array(), pick.wager.as_array()) else {
+        return (0, Vec::new());
+    };
+
+    let games = group
+        .spreads
+        .iter()
+        .zip(choices)
+        .zip(wagers)
+        .map(|((spread, choice), wager)| {
+            two_sided(
+                choice.as_str(),
+                ["home", "away"],
+                spread.answer.as_deref(),
+                wager.as_i64().unwrap_or_default() as i32,
+                push_rule,
+            )
+        })
+        .collect_vec();
+
+    (
+        games.iter().map(|(points, _)| points).sum(),
+        games.into_iter().map(|(_, correct)| correct).collect(),
+    )
+}
+
+fn user_input_points(input: &UserInput, pick: &Pick) -> (i32, Option<bool>) {
+    match (&input.acceptable_answers, choice(pick)) {
+        (Some(answers), Some(choice)) if answers.contains(choice) => (wager(pick), Some(true)),
+        (Some(_), _) => (0, Some(false)),
+        _ => (0, None),
+    }
+}
+
+fn moneyline_points(moneyline: &Moneyline, pick: &Pick) -> (i32, Option<bool>) {
+    two_sided(
+        choice(pick),
+        ["home", "away"],
+        moneyline.answer.as_deref(),
+        wager(pick),
+        PushRule::Zero,
+    )
+}
+
+fn over_under_points(
+    over_under: &OverUnder,
+    pick: &Pick,
+    push_rule: PushRule,
+) -> (i32, Option<bool>) {
+    two_sided(
+        choice(pick),
+        ["over", "under"],
+        over_under.answer.as_deref(),
+        wager(pick),
+        push_rule,
+    )
+}
+
+fn multiple_choice_points(multiple_choice: &MultipleChoice, pick: &Pick) -> (i32, Option<bool>) {
+    match (choice(pick), multiple_choice.answer.as_deref()) {
+        (_, None | Some("unpicked")) => (0, None),
+        (Some(choice), Some(answer)) if choice == answer => (wager(pick), Some(true)),
+        _ => (0, Some(false)),
+    }
+}
+
+/// Closest scoring needs every guess on the event, so the full set of picks is passed alongside
+/// the one being scored. Under distance scoring any guess that still earns points counts as correct
+fn numeric_points(numeric: &Numeric, pick: &Pick, picks: &[Pick]) -> (i32, Option<bool>) {
+    let Some(answer) = numeric.answer else {
+        return (0, None);
+    };
+    let distance = |pick: &Pick| pick.choice.as_f64().map(|guess| (guess - answer).abs());
+    let Some(pick_distance) = distance(pick) else {
+        return (0, Some(false));
+    };
+
+    let points = match numeric.scoring {
+        NumericScoring::Closest => {
+            let closest = picks
+                .iter()
+                .filter_map(distance)
+                .fold(f64::INFINITY, f64::min);
+            if pick_distance == closest {
+                wager(pick)
+            } else {
+                0
+            }
+        }
+        NumericScoring::Distance => (wager(pick) - pick_distance.round() as i32).max(0),
+    };
+
+    (points, Some(points > 0))
+}
+
+fn survivor_points(survivor: &Survivor, pick: &Pick) -> (i32, Option<bool>) {
+    let team_id = pick.choice.as_i64().unwrap_or_default() as i32;
+    match survivor.survived(team_id) {
+        Some(true) => (wager(pick), Some(true)),
+        correct => (0, correct),
+    }
+}
+
+/// Scores every pick in a chapter against the answers currently stored on its events
+pub async fn grade_chapter(
+    chapter_id: i32,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), sqlx::Error> {
+    let events = sqlx::query_as!(
+        Event,
+        r#"	SELECT  id,
+                    book_id,
+                    chapter_id,
+                    is_open,
+                    contents AS "contents: Json<EventContent>",
+                    event_type AS "event_type: EventType"
+			FROM events
+			WHERE chapter_id = $1
+		"#,
+        chapter_id
+    )
+    .fetch_all(&mut **transaction)
+    .await?;
+
+    let push_rule = sqlx::query_scalar!(
+        r#"
+        SELECT b.push_rule AS "push_rule: PushRule"
+        FROM chapters AS c
+        JOIN books AS b ON c.book_id = b.id
+        WHERE c.id = $1
+        "#,
+        chapter_id
+    )
+    .fetch_one(&mut **transaction)
+    .await?;
+
+    let picks = sqlx::query_as!(
+        Pick,
+        "
+        SELECT id, book_id, chapter_id, event_id, user_id, wager, choice, points
+        FROM picks
+        WHERE chapter_id = $1
+        ",
+        chapter_id
+    )
+    .fetch_all(&mut **transaction)
+    .await?
+    .into_iter()
+    .into_group_map_by(|pick| pick.event_id);
+
+    let scores = events
+        .iter()
+        .flat_map(|event| {
+            score_event(
+                &event.contents,
+                picks.get(&event.id).map(Vec::as_slice).unwrap_or_default(),
+                push_rule,
+            )
+        })
+        .collect_vec();
+
+    sqlx::query!(
+        "
+        UPDATE picks AS p
+        SET points = s.points, correct = s.correct
+        FROM UNNEST($1::INT[], $2::INT[], $3::JSONB[]) AS s(id, points, correct)
+        WHERE p.id = s.id
+        ",
+        &scores.iter().map(|score| score.pick_id).collect_vec(),
+        &scores.iter().map(|score| score.points).collect_vec(),
+        &scores
+            .iter()
+            .map(|score| score.correct.clone())
+            .collect_vec()
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::db::{
+        spread::{Spread, WagerMode},
+        survivor::SurvivorGame,
+    };
+
+    fn pick(id: i32, choice: Value, wager: Value) -> Pick {
+        Pick {
+            id,
+            book_id: 1,
+            chapter_id: 1,
+            event_id: 1,
+            user_id: id,
+            wager,
+            choice,
+            points: None,
+        }
+    }
+
+    fn grades(event: &EventContent, picks: &[Pick], push_rule: PushRule) -> Vec<(i32, Value)> {
+        score_event(event, picks, push_rule)
+            .into_iter()
+            .map(|score| (score.points, score.correct))
+            .collect()
+    }
+
+    fn spread(answer: Option<&str>) -> Spread {
+        Spread {
+            home_id: 1,
+            away_id: 2,
+            home_spread: -3.5,
+            start_time: None,
+            answer: answer.map(String::from),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn spread_group() {
+        let event = EventContent::SpreadGroup(SpreadGroup {
+            spreads: vec![
+                spread(Some("home")),
+                spread(Some("away")),
+                spread(Some("push")),
+                spread(None),
+            ],
+            wager_mode: WagerMode::Confidence,
+        });
+
+        let cases = [
+            (
+                json!(["home", "away", "home", "away"]),
+                PushRule::Zero,
+                (3, json!([true, true, null, null])),
+            ),
+            (
+                json!(["home", "away", "home", "away"]),
+                PushRule::Refund,
+                (6, json!([true, true, null, null])),
+            ),
+            (
+                json!(["home", "away", "home", "away"]),
+                PushRule::Half,
+                (4, json!([true, true, null, null])),
+            ),
+            (
+                json!(["away", "home", "away", "home"]),
+                PushRule::Zero,
+                (0, json!([false, false, null, null])),
+            ),
+            (
+                json!(["away", "home", "away", "home"]),
+                PushRule::Refund,
+                (3, json!([false, false, null, null])),
+            ),
+            (
+                json!(["home", "unpicked", "home", "away"]),
+                PushRule::Zero,
+                (1, json!([true, null, null, null])),
+            ),
+            (json!(["home"]), PushRule::Zero, (1, json!([true]))),
+            (json!("home"), PushRule::Zero, (0, json!([]))),
+        ];
+
+        for (choice, push_rule, expected) in cases {
+            let picks = [pick(1, choice.clone(), json!([1, 2, 3, 4]))];
+            assert_eq!(
+                grades(&event, &picks, push_rule),
+                [expected],
+                "{choice} with {push_rule:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn user_input() {
+        let answered = EventContent::UserInput(UserInput {
+            title: "Who wins MVP?".into(),
+            description: None,
+            points: 2,
+            acceptable_answers: Some(["Mahomes".into(), "Patrick Mahomes".into()].into()),
+        });
+        let unanswered = EventContent::UserInput(UserInput {
+            title: "Who wins MVP?".into(),
+            description: None,
+            points: 2,
+            acceptable_answers: None,
+        });
+
+        let cases = [
+            (&answered, json!("Mahomes"), (2, json!(true))),
+            (&answered, json!("Patrick Mahomes"), (2, json!(true))),
+            (&answered, json!("Allen"), (0, json!(false))),
+            (&unanswered, json!("Mahomes"), (0, json!(null))),
+        ];
+
+        for (event, choice, expected) in cases {
+            let picks = [pick(1, choice.clone(), json!(2))];
+            assert_eq!(
+                grades(event, &picks, PushRule::Zero),
+                [expected],
+                "{choice}"
+            );
+        }
+    }
+
+    #[test]
+    fn moneyline() {
+        let cases = [
+            (Some("home"), json!("home"), (3, json!(true))),
+            (Some("home"), json!("away"), (0, json!(false))),
+            (Some("unpicked"), json!("home"), (0, json!(null))),
+            (None, json!("home"), (0, json!(null))),
+        ];
+
+        for (answer, choice, expected) in cases {
+            let event = EventContent::Moneyline(Moneyline {
+                home_id: 1,
+                away_id: 2,
+                points: 3,
+                answer: answer.map(String::from),
+            });
+            let picks = [pick(1, choice.clone(), json!(3))];
+            assert_eq!(
+                grades(&event, &picks, PushRule::Zero),
+                [expected],
+                "{choice} against {answer:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn over_under() {
+        let cases = [
+            (
+                Some("over"),
+                json!("over"),
+                PushRule::Zero,
+                (4, json!(true)),
+            ),
+            (
+                Some("over"),
+                json!("under"),
+                PushRule::Refund,
+                (0, json!(false)),
+            ),
+            (
+                Some("push"),
+                json!("under"),
+                PushRule::Zero,
+                (0, json!(null)),
+            ),
+            (
+                Some("push"),
+                json!("under"),
+                PushRule::Refund,
+                (4, json!(null)),
+            ),
+            (
+                Some("push"),
+                json!("over"),
+                PushRule::Half,
+                (2, json!(null)),
+            ),
+            (
+                Some("void"),
+                json!("over"),
+                PushRule::Refund,
+                (0, json!(null)),
+            ),
+            (None, json!("over"), PushRule::Refund, (0, json!(null))),
+        ];
+
+        for (answer, choice, push_rule, expected) in cases {
+            let event = EventContent::OverUnder(OverUnder {
+                home_id: 1,
+                away_id: 2,
+                total: 44.5,
+                points: 4,
+                answer: answer.map(String::from),
+            });
+            let picks = [pick(1, choice.clone(), json!(4))];
+            assert_eq!(
+                grades(&event, &picks, push_rule),
+                [expected],
+                "{choice} against {answer:?} with {push_rule:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_choice() {
+        let cases = [
+            (Some("Red"), json!("Red"), (2, json!(true))),
+            (Some("Red"), json!("Blue"), (0, json!(false))),
+            (Some("unpicked"), json!("Red"), (0, json!(null))),
+            (None, json!("Red"), (0, json!(null))),
+        ];
+
+        for (answer, choice, expected) in cases {
+            let event = EventContent::MultipleChoice(MultipleChoice {
+                title: "Gatorade color".into(),
+                description: None,
+                points: 2,
+                options: vec!["Red".into(), "Blue".into()],
+                answer: answer.map(String::from),
+            });
+            let picks = [pick(1, choice.clone(), json!(2))];
+            assert_eq!(
+                grades(&event, &picks, PushRule::Zero),
+                [expected],
+                "{choice} against {answer:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn numeric() {
+        let guesses = [json!(40.0), json!(48.0), json!(52.0), json!(60.4)];
+        let cases = [
+            (NumericScoring::Closest, Some(50.0), [0, 5, 5, 0]),
+            (NumericScoring::Closest, Some(60.0), [0, 0, 0, 5]),
+            (NumericScoring::Distance, Some(50.0), [0, 3, 3, 0]),
+            (NumericScoring::Distance, Some(41.0), [4, 0, 0, 0]),
+            (NumericScoring::Closest, None, [0, 0, 0, 0]),
+        ];
+
+        for (scoring, answer, expected) in cases {
+            let event = EventContent::Numeric(Numeric {
+                title: "Total points".into(),
+                description: None,
+                points: 5,
+                scoring,
+                answer,
+            });
+            let picks = guesses
+                .iter()
+                .enumerate()
+                .map(|(i, guess)| pick(i as i32, guess.clone(), json!(5)))
+                .collect::<Vec<_>>();
+            let expected = expected.map(|points| match answer {
+                Some(_) => (points, json!(points > 0)),
+                None => (points, json!(null)),
+            });
+            assert_eq!(
+                grades(&event, &picks, PushRule::Zero),
+                expected,
+                "{scoring:?} against {answer:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn survivor() {
+        let event = EventContent::Survivor(Survivor {
+            games: vec![
+                SurvivorGame {
+                    home_id: 1,
+                    away_id: 2,
+                    winner: Some("home".into()),
+                },
+                SurvivorGame {
+                    home_id: 3,
+                    away_id: 4,
+                    winner: Some("push".into()),
+                },
+                SurvivorGame {
+                    home_id: 5,
+                    away_id: 6,
+                    winner: None,
+                },
+            ],
+        });
+
+        let cases = [
+            (1, (1, json!(true))),
+            (2, (0, json!(false))),
+            (3, (1, json!(true))),
+            (4, (1, json!(true))),
+            (5, (0, json!(null))),
+            (7, (0, json!(null))),
+        ];
+
+        for (team_id, expected) in cases {
+            let picks = [pick(1, json!(team_id), json!(1))];
+            assert_eq!(
+                grades(&event, &picks, PushRule::Zero),
+                [expected],
+                "team {team_id}"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_picks() {
+        let event = EventContent::Moneyline(Moneyline {
+            home_id: 1,
+            away_id: 2,
+            points: 3,
+            answer: Some("home".into()),
+        });
+
+        assert!(score_event(&event, &[], PushRule::Refund).is_empty());
+
+        let event = EventContent::Numeric(Numeric {
+            title: "Total points".into(),
+            description: None,
+            points: 5,
+            scoring: NumericScoring::Closest,
+            answer: Some(50.0),
+        });
+        let picks = [
+            pick(1, json!(null), json!(5)),
+            pick(2, json!(10.0), json!(5)),
+        ];
+        assert_eq!(
+            grades(&event, &picks, PushRule::Zero),
+            [(0, json!(false)), (5, json!(true))]
+        );
+    }
+}
@@ -1,4 +1,16 @@
-use crate::chapter::create::AddEventType;
+use std::collections::HashMap;
+
+use crate::{
+    chapter::create::AddEventType,
+    db::{
+        event::{Event, EventContent},
+        numeric::NumericScoring,
+        spread::{Spread, WagerMode},
+        survivor::SurvivorGame,
+    },
+};
+
+type Teams = HashMap<i32, (String, Option<String>)>;
 
 pub fn markup(ty: AddEventType) -> maud::Markup {
     maud::html! {
@@ -7,13 +19,21 @@ pub fn markup(ty: AddEventType) -> maud::Markup {
                 fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
                     h3 class="font-semibold" { "Spread Group" }
                     input type="hidden" name="type" value="spread-group";
-                    (spread_group_spread())
+                    div title="wager-mode" {
+                        select name="wager-mode" form="submit-events" class="m-1 border border-green-300" {
+                            option value="confidence" { "Confidence points" }
+                            option value="flat" { "One point per game" }
+                            option value="bankroll" { "Bankroll" }
+                        }
+                        input type="number" name="bankroll" placeholder="Bankroll (bankroll mode only)" form="submit-events" min="1" class="m-1 text-center border border-green-300";
+                    }
+                    (spread_group_spread(None, &Teams::new()))
                     button hx-get="add?type=spread-group-append" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
                         "Add Spread"
                     }
                 }
             },
-            AddEventType::SpreadGroupAppend => (spread_group_spread()),
+            AddEventType::SpreadGroupAppend => (spread_group_spread(None, &Teams::new())),
             AddEventType::UserInput => {
                 fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
                     h3 class="font-semibold" { "User Input" }
@@ -25,29 +45,280 @@ pub fn markup(ty: AddEventType) -> maud::Markup {
                     input type="number" name="points" placeholder="Number of Points" value="1" required class="text-center";
                 }
             }
+            AddEventType::Moneyline => {
+                fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Moneyline" }
+                    input type="hidden" name="type" value="moneyline";
+                    p { "Team 1 (Home)" }
+                    (team_picker(1, "home-id", None))
+                    p { "Team 2 (Away)" }
+                    (team_picker(2, "away-id", None))
+                    input type="number" name="points" placeholder="Number of Points" value="1" required class="text-center";
+                }
+            }
+            AddEventType::MultipleChoice => {
+                fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Multiple Choice" }
+                    input type="hidden" name="type" value="multiple-choice";
+                    input type="text" name="title" placeholder="Title" form="submit-events" required class="text-center border border-green-300";
+                    br;
+                    input type="text" name="description" placeholder="Description (optional)" form="submit-events" class="text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value="1" required class="text-center";
+                    p { "Options" }
+                    (multiple_choice_option(None))
+                    (multiple_choice_option(None))
+                    button hx-get="add?type=multiple-choice-option" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                        "Add Option"
+                    }
+                }
+            }
+            AddEventType::MultipleChoiceOption => (multiple_choice_option(None)),
+            AddEventType::Numeric => {
+                fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Numeric Prediction" }
+                    input type="hidden" name="type" value="numeric";
+                    input type="text" name="title" placeholder="Title" form="submit-events" required class="text-center border border-green-300";
+                    br;
+                    input type="text" name="description" placeholder="Description (optional)" form="submit-events" class="text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value="1" required class="text-center";
+                    br;
+                    select name="scoring" form="submit-events" class="m-1 border border-green-300" {
+                        option value="closest" { "Closest guess wins" }
+                        option value="distance" { "Lose a point per unit off" }
+                    }
+                }
+            }
+            AddEventType::Survivor => {
+                fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Survivor Games" }
+                    input type="hidden" name="type" value="survivor";
+                    (survivor_game(None, &Teams::new()))
+                    button hx-get="add?type=survivor-append" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                        "Add Game"
+                    }
+                }
+            }
+            AddEventType::SurvivorAppend => (survivor_game(None, &Teams::new())),
+            AddEventType::OverUnder => {
+                fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Over/Under" }
+                    input type="hidden" name="type" value="over-under";
+                    p { "Team 1 (Home)" }
+                    (team_picker(1, "home-id", None))
+                    p { "Team 2 (Away)" }
+                    (team_picker(2, "away-id", None))
+                    input type="number" name="total" placeholder="Total Points Line" form="submit-events" step="0.5" min="0" required class="m-1 text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value="1" required class="text-center";
+                }
+            }
         }
     }
 }
 
-fn spread_group_spread() -> maud::Markup {
+/// An event that already exists, filled in with its current contents for editing. Removing
+/// the fieldset removes the event when the edit is submitted
+pub fn existing(event: &Event, teams: &Teams, pick_count: i64) -> maud::Markup {
+    prefilled(
+        &event.contents.0,
+        teams,
+        maud::html! {
+            input type="hidden" name="event-id" value=(event.id);
+            @if pick_count > 0 {
+                p class="text-sm text-orange-600" { (pick_count) " pick(s) already made on this event" }
+            }
+        },
+    )
+}
+
+/// A new event copied from a chapter template
+pub fn from_template(contents: &EventContent, teams: &Teams) -> maud::Markup {
+    prefilled(contents, teams, maud::html!())
+}
+
+fn prefilled(contents: &EventContent, teams: &Teams, header: maud::Markup) -> maud::Markup {
+    maud::html! {
+        fieldset form="submit-events" name="events" me-insert="array" class="self-center justify-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            (header)
+            @match contents {
+                EventContent::SpreadGroup(group) => {
+                    h3 class="font-semibold" { "Spread Group" }
+                    input type="hidden" name="type" value="spread-group";
+                    div title="wager-mode" {
+                        select name="wager-mode" form="submit-events" class="m-1 border border-green-300" {
+                            option value="confidence" selected[group.wager_mode == WagerMode::Confidence] { "Confidence points" }
+                            option value="flat" selected[group.wager_mode == WagerMode::Flat] { "One point per game" }
+                            option value="bankroll" selected[matches!(group.wager_mode, WagerMode::Bankroll { .. })] { "Bankroll" }
+                        }
+                        @let bankroll = match group.wager_mode { WagerMode::Bankroll { total } => Some(total), _ => None };
+                        input type="number" name="bankroll" placeholder="Bankroll (bankroll mode only)" value=[bankroll] form="submit-events" min="1" class="m-1 text-center border border-green-300";
+                    }
+                    @for spread in &group.spreads {
+                        (spread_group_spread(Some(spread), teams))
+                    }
+                    button hx-get="add?type=spread-group-append" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                        "Add Spread"
+                    }
+                }
+                EventContent::UserInput(input) => {
+                    h3 class="font-semibold" { "User Input" }
+                    input type="hidden" name="type" value="user-input";
+                    input type="text" name="title" placeholder="Title" value=(input.title) form="submit-events" required class="text-center border border-green-300";
+                    br;
+                    input type="text" name="description" placeholder="Description (optional)" value=[input.description.as_deref()] form="submit-events" class="text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value=(input.points) required class="text-center";
+                }
+                EventContent::Moneyline(moneyline) => {
+                    h3 class="font-semibold" { "Moneyline" }
+                    input type="hidden" name="type" value="moneyline";
+                    p { "Team 1 (Home)" }
+                    (team_picker(1, "home-id", Some((moneyline.home_id, teams))))
+                    p { "Team 2 (Away)" }
+                    (team_picker(2, "away-id", Some((moneyline.away_id, teams))))
+                    input type="number" name="points" placeholder="Number of Points" value=(moneyline.points) required class="text-center";
+                }
+                EventContent::OverUnder(over_under) => {
+                    h3 class="font-semibold" { "Over/Under" }
+                    input type="hidden" name="type" value="over-under";
+                    p { "Team 1 (Home)" }
+                    (team_picker(1, "home-id", Some((over_under.home_id, teams))))
+                    p { "Team 2 (Away)" }
+                    (team_picker(2, "away-id", Some((over_under.away_id, teams))))
+                    input type="number" name="total" placeholder="Total Points Line" value=(over_under.total) form="submit-events" step="0.5" min="0" required class="m-1 text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value=(over_under.points) required class="text-center";
+                }
+                EventContent::MultipleChoice(multiple_choice) => {
+                    h3 class="font-semibold" { "Multiple Choice" }
+                    input type="hidden" name="type" value="multiple-choice";
+                    input type="text" name="title" placeholder="Title" value=(multiple_choice.title) form="submit-events" required class="text-center border border-green-300";
+                    br;
+                    input type="text" name="description" placeholder="Description (optional)" value=[multiple_choice.description.as_deref()] form="submit-events" class="text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value=(multiple_choice.points) required class="text-center";
+                    p { "Options" }
+                    @for option in &multiple_choice.options {
+                        (multiple_choice_option(Some(option)))
+                    }
+                    button hx-get="add?type=multiple-choice-option" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                        "Add Option"
+                    }
+                }
+                EventContent::Numeric(numeric) => {
+                    h3 class="font-semibold" { "Numeric Prediction" }
+                    input type="hidden" name="type" value="numeric";
+                    input type="text" name="title" placeholder="Title" value=(numeric.title) form="submit-events" required class="text-center border border-green-300";
+                    br;
+                    input type="text" name="description" placeholder="Description (optional)" value=[numeric.description.as_deref()] form="submit-events" class="text-center border border-green-300";
+                    br;
+                    input type="number" name="points" placeholder="Number of Points" value=(numeric.points) required class="text-center";
+                    br;
+                    select name="scoring" form="submit-events" class="m-1 border border-green-300" {
+                        option value="closest" selected[numeric.scoring == NumericScoring::Closest] { "Closest guess wins" }
+                        option value="distance" selected[numeric.scoring == NumericScoring::Distance] { "Lose a point per unit off" }
+                    }
+                }
+                EventContent::Survivor(survivor) => {
+                    h3 class="font-semibold" { "Survivor Games" }
+                    input type="hidden" name="type" value="survivor";
+                    @for game in &survivor.games {
+                        (survivor_game(Some(game), teams))
+                    }
+                    button hx-get="add?type=survivor-append" hx-swap="beforebegin" class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                        "Add Game"
+                    }
+                }
+            }
+            br;
+            (remove_button("Remove Event"))
+        }
+    }
+}
+
+fn remove_button(label: &str) -> maud::Markup {
+    maud::html! {
+        button type="button" onclick="this.parentElement.remove()" class="px-2 py-1 mt-1 text-sm font-bold text-white bg-red-600 rounded hover:bg-red-700" {
+            (label)
+        }
+    }
+}
+
+fn spread_group_spread(spread: Option<&Spread>, teams: &Teams) -> maud::Markup {
     maud::html! {
         fieldset name="spreads" me-insert="array" class="border border-black rounded-md" {
             p { "Spread Team 1 (Home)" }
-            div title="team-1" hx-target="this" {
-                input type="search" name="name" placeholder="Team 1" autocomplete="off" class="border border-green-300" hx-get="/team-search" hx-trigger="input changed delay:500ms, search" hx-vals=r#"{"location":"home-id"}"# hx-target="next ul" ;
-                ul {}
-                input form="submit-events" type="text" name="team-1-validate" class="border border-green-300" style="opacity: 0; width: 0;" oninvalid="this.setCustomValidity('Missing Spread Team 1')" required;
-            }
+            (team_picker(1, "home-id", spread.map(|spread| (spread.home_id, teams))))
 
             p { "Spread Team 2 (Away)" }
-            div title="team-2" hx-target="this" {
-                input type="search" name="name" placeholder="Team 2" autocomplete="off" class="border border-green-300" hx-get="/team-search" hx-trigger="input changed delay:500ms, search" hx-vals=r#"{"location":"away-id"}"# hx-target="next ul" ;
-                ul {}
-                input form="submit-events" type="text" name="team-2-validate" class="border border-green-300" style="opacity: 0; width: 0;" oninvalid="this.setCustomValidity('Missing Spread Team 2')" required;
-            }
+            (team_picker(2, "away-id", spread.map(|spread| (spread.away_id, teams))))
 
             div title="spread-amount" {
-                input type="number" name="home-spread" placeholder="Home Spread Amount" form="submit-events" step="0.5" required class="m-1 border border-green-300";
+                input type="number" name="home-spread" placeholder="Home Spread Amount" value=[spread.map(|spread| spread.home_spread)] form="submit-events" step="0.5" required class="m-1 border border-green-300";
+            }
+
+            div title="start-time" {
+                p { "Kickoff (optional)" }
+                @if let Some(start_time) = spread.and_then(|spread| spread.start_time) {
+                    p class="text-sm" { "Currently " (crate::templates::local_time(start_time)) }
+                    (crate::templates::utc_datetime_input_value("start-time", None, Some(start_time)))
+                } @else {
+                    (crate::templates::utc_datetime_input("start-time", None))
+                }
+            }
+
+            div title="notes" {
+                textarea name="notes" rows="2" placeholder="Game notes (optional, markdown)" form="submit-events" class="m-1 border border-green-300" { (spread.and_then(|spread| spread.notes.as_deref()).unwrap_or_default()) }
+            }
+
+            @if spread.is_some() {
+                (remove_button("Remove Spread"))
+            }
+        }
+    }
+}
+
+fn survivor_game(game: Option<&SurvivorGame>, teams: &Teams) -> maud::Markup {
+    maud::html! {
+        fieldset name="games" me-insert="array" class="border border-black rounded-md" {
+            p { "Team 1 (Home)" }
+            (team_picker(1, "home-id", game.map(|game| (game.home_id, teams))))
+
+            p { "Team 2 (Away)" }
+            (team_picker(2, "away-id", game.map(|game| (game.away_id, teams))))
+
+            @if game.is_some() {
+                (remove_button("Remove Game"))
+            }
+        }
+    }
+}
+
+fn multiple_choice_option(option: Option<&str>) -> maud::Markup {
+    maud::html! {
+        div {
+            input type="text" name="options" me-insert="array" placeholder="Option" value=[option] form="submit-events" required class="m-1 text-center border border-green-300";
+        }
+    }
+}
+
+/// Searches for a team. When editing, the current team stays selected until another is picked
+fn team_picker(team_number: u8, location: &str, selected: Option<(i32, &Teams)>) -> maud::Markup {
+    maud::html! {
+        div title={"team-"(team_number)} hx-target="this" {
+            input type="search" name="name" placeholder={"Team "(team_number)} autocomplete="off" class="border border-green-300" hx-get="/team-search" hx-trigger="input changed delay:500ms, search" hx-vals={r#"{"location":""#(location)r#""}"#} hx-target="next ul" ;
+            ul {}
+            @if let Some((team_id, teams)) = selected {
+                @let (name, logo) = teams.get(&team_id).cloned().unwrap_or_default();
+                div {
+                    p { (name) }
+                    img src=(logo.unwrap_or_default()) class="object-scale-down w-20 h-20" alt="";
+                    input type="hidden" name=(location) value=(team_id);
+                }
+            } @else {
+                input form="submit-events" type="text" name={"team-"(team_number)"-validate"} class="border border-green-300" style="opacity: 0; width: 0;" oninvalid={"this.setCustomValidity('Missing Team "(team_number)"')"} required;
             }
         }
     }
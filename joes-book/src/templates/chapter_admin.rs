@@ -3,7 +3,12 @@ use std::collections::HashMap;
 use crate::db::{
     chapter::Chapter,
     event::{Event, EventContent},
+    moneyline::Moneyline,
+    multiple_choice::MultipleChoice,
+    numeric::Numeric,
+    over_under::OverUnder,
     spread::Spread,
+    survivor::Survivor,
     user_input::UserInput,
 };
 
@@ -38,6 +43,39 @@ pub fn markup(
                     (chapter_open_button(chapter.is_open))
 
                     (chapter_visible_button(chapter.is_visible))
+
+                    a href="edit/" {
+                        button class="px-2 py-2 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Edit Chapter" }
+                    }
+                    button
+                        hx-post="clone"
+                        hx-confirm="Copy this chapter's events into a new hidden chapter?"
+                        class="px-2 py-2 mt-1 ml-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" { "Clone Chapter" }
+                    form hx-post="save-template" hx-on--after-request="if (event.detail.successful) this.reset()" class="mt-2" {
+                        input type="text" name="name" required placeholder="Template name" class="border border-green-300";
+                        button type="submit" class="px-2 py-1 ml-1 font-bold text-white bg-green-600 rounded hover:bg-green-700" { "Save as Template" }
+                    }
+
+                    @for (label, time) in [("Reveals", chapter.reveal_at), ("Opens", chapter.opens_at), ("Locks", chapter.locks_at)] {
+                        @if let Some(time) = time {
+                            p class="text-sm" { "Scheduled to " (label.to_lowercase()) ": " (crate::templates::local_time(time)) }
+                        }
+                    }
+                }
+
+                div class="flex flex-col items-center p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                    h3 class="font-semibold" { "Invite a Guest" }
+                    p class="text-xs" { "Guests can only see the chapters they are invited to" }
+                    input
+                        name="username"
+                        hx-get="guest-search"
+                        hx-trigger="input changed delay:200ms, search"
+                        hx-target="next ul"
+                        type="search"
+                        autocomplete="off"
+                        placeholder="username"
+                        class="border border-green-300";
+                    ul {}
                 }
 
                 div class="flex items-center justify-center" {
@@ -51,23 +89,62 @@ pub fn markup(
                     }
                 }
 
+                div class="flex items-center justify-center" {
+                    details class="flex items-center w-max" {
+                        summary class="p-3 my-1 align-middle bg-green-500 rounded-lg shadow-md select-none" {
+                            "Pick History"
+                        }
+                        div hx-get="pick-history" hx-trigger="toggle once from:closest details" hx-swap="outerHTML" class="flex items-center" {
+                            "Loading..."
+                        }
+                    }
+                }
+
                 form hx-post="." hx-ext="my-enc" hx-swap="afterend" {
                     @for (i, event) in events.into_iter().enumerate() {
                         fieldset name="events" me-insert="array" class="flex items-center justify-center" {
                             input type="hidden" name="event-id" value=(event.id);
+                            (event_open_button(event.id, event.is_open))
                             @match event.contents.0 {
-                                EventContent::SpreadGroup(spreads) => {
+                                EventContent::SpreadGroup(group) => {
                                     input type="hidden" name="type" value="spread-group";
-                                    (spread_group(spreads, i, &relevent_teams))
+                                    (spread_group(group.spreads, i, &relevent_teams))
                                 },
                                 EventContent::UserInput(input) => {
                                     input type="hidden" name="type" value="user-input";
                                     (user_input(input, event.id, i))
                                 }
+                                EventContent::Moneyline(moneyline) => {
+                                    input type="hidden" name="type" value="moneyline";
+                                    (moneyline_answer(moneyline, i, &relevent_teams))
+                                }
+                                EventContent::MultipleChoice(multiple_choice) => {
+                                    input type="hidden" name="type" value="multiple-choice";
+                                    (multiple_choice_answer(multiple_choice, i))
+                                }
+                                EventContent::Survivor(survivor) => {
+                                    input type="hidden" name="type" value="survivor";
+                                    (survivor_answer(survivor, i, &relevent_teams))
+                                }
+                                EventContent::Numeric(numeric) => {
+                                    input type="hidden" name="type" value="numeric";
+                                    (numeric_answer(numeric))
+                                }
+                                EventContent::OverUnder(over_under) => {
+                                    input type="hidden" name="type" value="over-under";
+                                    (over_under_answer(over_under, i, &relevent_teams))
+                                }
                             }
                         }
                     }
 
+                    @if let Some(tiebreaker) = &chapter.tiebreaker {
+                        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                            h3 class="font-semibold" { "Tiebreaker" }
+                            h4 { (tiebreaker) }
+                            input type="number" name="tiebreaker-answer" step="any" placeholder="Actual Value" value=[chapter.tiebreaker_answer] class="p-1 text-center border border-green-300 rounded-lg";
+                        }
+                    }
                     button type="submit" class="px-2 py-2 mt-1 mb-10 font-bold text-white bg-green-600 rounded hover:bg-green-700" {
                         "Submit"
                     }
@@ -128,6 +205,24 @@ pub fn chapter_visible_button(is_visible: bool) -> maud::Markup {
     }
 }
 
+pub fn event_open_button(event_id: i32, is_open: bool) -> maud::Markup {
+    maud::html! {
+        div hx-target="this" {
+            @if is_open {
+                p { "Event Status: Open" }
+                button type="button" hx-post={"event-open?event-id="(event_id)"&toggle=false"} class="p-0.5 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Close"
+                }
+            } @else {
+                p { "Event Status: Closed" }
+                button type="button" hx-post={"event-open?event-id="(event_id)"&toggle=true"} class="p-0.5 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Open"
+                }
+            }
+        }
+    }
+}
+
 fn spread_group(
     spreads: Vec<Spread>,
     index: usize,
@@ -167,6 +262,12 @@ fn spread_group(
                         p class="px-1 font-semibold" { "Push" }
                     }
                 }
+                div {
+                    input type="radio" name={"selections["(index)"-"(i)"]"} me-insert="array" class="absolute opacity-0 peer" value="void" id={(index)"-"(i)"-void"} checked[spread.answer == Some("void".into())];
+                    label for={(index)"-"(i)"-void"} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-gray-700 peer-checked:bg-gray-500 peer-checked:border-gray-600 hover:bg-gray-100" {
+                        p class="px-1 font-semibold" { "Void (Cancelled)" }
+                    }
+                }
                 div {
                     input type="radio" name={"selections["(index)"-"(i)"]"} me-insert="array" class="absolute opacity-0 peer" value="unpicked" id={(index)"-"(i)"-unpicked"} checked[spread.answer.is_none() || spread.answer == Some("unpicked".into())];
                     label for={(index)"-"(i)"-unpicked"} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
@@ -195,3 +296,115 @@ fn user_input(input: UserInput, event_id: i32, _index: usize) -> maud::Markup {
         }
     }
 }
+
+fn moneyline_answer(
+    moneyline: Moneyline,
+    index: usize,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> maud::Markup {
+    maud::html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            div class="grid grid-flow-col grid-cols-2 p-2" {
+                @for (side, team_id) in [("home", moneyline.home_id), ("away", moneyline.away_id)] {
+                    div class="col-span-1 mx-0.5" {
+                        input type="radio" name={"selection["(index)"]"} class="absolute opacity-0 peer" value=(side) id={(index)"-"(side)} checked[moneyline.answer.as_deref() == Some(side)];
+                        label for={(index)"-"(side)} class="inline-grid w-full h-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                            div {
+                                p class="font-semibold" { @if side == "home" { "Home" } @else { "Away" } }
+                                img src=(relevent_teams[&team_id].1.to_owned().unwrap_or_default()) width="150" height="150" alt="Team Logo";
+                                p { (relevent_teams[&team_id].0) }
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                input type="radio" name={"selection["(index)"]"} class="absolute opacity-0 peer" value="unpicked" id={(index)"-unpicked"} checked[moneyline.answer.is_none() || moneyline.answer.as_deref() == Some("unpicked")];
+                label for={(index)"-unpicked"} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                    p class="px-1 font-semibold" { "Unpicked" }
+                }
+            }
+        }
+    }
+}
+
+fn over_under_answer(
+    over_under: OverUnder,
+    index: usize,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> maud::Markup {
+    maud::html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="font-semibold" {
+                (relevent_teams[&over_under.away_id].0) " at " (relevent_teams[&over_under.home_id].0) " (O/U " (over_under.total) ")"
+            }
+            @for (value, label) in [("over", "Over"), ("under", "Under"), ("push", "Push"), ("unpicked", "Unpicked")] {
+                div {
+                    input type="radio" name={"selection["(index)"]"} class="absolute opacity-0 peer" value=(value) id={(index)"-"(value)} checked[over_under.answer.as_deref() == Some(value) || (value == "unpicked" && over_under.answer.is_none())];
+                    label for={(index)"-"(value)} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                        p class="px-1 font-semibold" { (label) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn multiple_choice_answer(multiple_choice: MultipleChoice, index: usize) -> maud::Markup {
+    maud::html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="font-semibold" { (multiple_choice.title) }
+            @for (i, option) in multiple_choice.options.iter().enumerate() {
+                div {
+                    input type="radio" name={"selection["(index)"]"} class="absolute opacity-0 peer" value=(option) id={(index)"-option-"(i)} checked[multiple_choice.answer.as_ref() == Some(option)];
+                    label for={(index)"-option-"(i)} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                        p class="px-1 font-semibold" { (option) }
+                    }
+                }
+            }
+            div {
+                input type="radio" name={"selection["(index)"]"} class="absolute opacity-0 peer" value="unpicked" id={(index)"-unpicked"} checked[multiple_choice.answer.is_none() || multiple_choice.answer.as_deref() == Some("unpicked")];
+                label for={(index)"-unpicked"} class="inline-grid w-10/12 p-5 pt-0 pb-0 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                    p class="px-1 font-semibold" { "Unpicked" }
+                }
+            }
+        }
+    }
+}
+
+fn numeric_answer(numeric: Numeric) -> maud::Markup {
+    maud::html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="font-semibold" { (numeric.title) }
+            @if let Some(description) = numeric.description {
+                h4 { (description) }
+            }
+            input type="number" name="answer" step="any" placeholder="Actual Value" value=[numeric.answer] class="p-1 text-center border border-green-300 rounded-lg";
+        }
+    }
+}
+
+fn survivor_answer(
+    survivor: Survivor,
+    index: usize,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> maud::Markup {
+    maud::html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="font-semibold" { "Survivor" }
+            @for (i, game) in survivor.games.into_iter().enumerate() {
+                div class="p-1 my-1 border border-gray-300 rounded-lg" {
+                    p { (relevent_teams[&game.away_id].0) " at " (relevent_teams[&game.home_id].0) }
+                    @for (value, label) in [("home", "Home Won"), ("away", "Away Won"), ("push", "Tie"), ("unpicked", "Unpicked")] {
+                        div class="inline-block" {
+                            input type="radio" name={"winners["(index)"-"(i)"]"} me-insert="array" class="absolute opacity-0 peer" value=(value) id={(index)"-"(i)"-"(value)} checked[game.winner.as_deref() == Some(value) || (value == "unpicked" && game.winner.is_none())];
+                            label for={(index)"-"(i)"-"(value)} class="inline-grid p-1 px-2 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                                p class="text-sm font-semibold" { (label) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,14 @@
-use crate::templates::authenticated;
+use crate::{
+    db::{book::BookType, template::ChapterTemplate},
+    templates::authenticated,
+};
 
-pub fn markup(username: &str, book_name: &str) -> maud::Markup {
+pub fn markup(
+    username: &str,
+    book_name: &str,
+    kind: BookType,
+    templates: &[ChapterTemplate],
+) -> maud::Markup {
     authenticated(
         username,
         Some("Create Chapter"),
@@ -19,13 +27,71 @@ pub fn markup(username: &str, book_name: &str) -> maud::Markup {
         }),
         Some(maud::html! {
             input type="text" name="chapter-name" class="border border-green-300" placeholder="Chapter Name" form="submit-events" required;
+            details class="my-2" {
+                summary { "Schedule (optional)" }
+                div class="flex flex-col items-center" {
+                    label {
+                        "Reveal At"
+                        (crate::templates::utc_datetime_input("reveal-at", Some("submit-events")))
+                    }
+                    label {
+                        "Opens At"
+                        (crate::templates::utc_datetime_input("opens-at", Some("submit-events")))
+                    }
+                    label {
+                        "Locks At"
+                        (crate::templates::utc_datetime_input("locks-at", Some("submit-events")))
+                    }
+                }
+            }
+            details class="my-2" {
+                summary { "Tiebreaker (optional)" }
+                input type="text" name="tiebreaker" class="border border-green-300" placeholder="e.g. Monday night total points" form="submit-events";
+            }
+            details class="my-2" {
+                summary { "Notes (optional)" }
+                textarea name="notes" rows="4" placeholder="Markdown shown above the picks" form="submit-events" class="w-full max-w-lg border border-green-300" {}
+            }
+            @if !templates.is_empty() {
+                div class="my-2" {
+                    select id="template-id" name="id" class="m-1 border border-green-300" {
+                        @for template in templates {
+                            option value=(template.id) { (template.name) }
+                        }
+                    }
+                    button hx-get="template" hx-include="#template-id" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-green-600 rounded hover:bg-green-700" {
+                        "Use Template"
+                    }
+                    button hx-delete="template" hx-include="#template-id" hx-target="#template-id option:checked" hx-swap="outerHTML" hx-confirm="Delete this template?" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-red-600 rounded hover:bg-red-700" {
+                        "Delete Template"
+                    }
+                }
+            }
             div id="event-spaces" {}
 
-            button hx-get="add?type=spread-group" hx-target="#event-spaces" hx-swap="beforeend" hx-on--after-on-load="this.remove()" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
-                "Add Spread Group"
-            }
-            button hx-get="add?type=user-input" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
-                "Add User Input"
+            @if kind == BookType::Survivor {
+                button hx-get="add?type=survivor" hx-target="#event-spaces" hx-swap="beforeend" hx-on--after-on-load="this.remove()" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Survivor Games"
+                }
+            } @else {
+                button hx-get="add?type=spread-group" hx-target="#event-spaces" hx-swap="beforeend" hx-on--after-on-load="this.remove()" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Spread Group"
+                }
+                button hx-get="add?type=user-input" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add User Input"
+                }
+                button hx-get="add?type=moneyline" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Moneyline"
+                }
+                button hx-get="add?type=over-under" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Over/Under"
+                }
+                button hx-get="add?type=multiple-choice" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Multiple Choice"
+                }
+                button hx-get="add?type=numeric" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Numeric Prediction"
+                }
             }
 
             form id="submit-events" hx-post="." hx-ext="my-enc" {
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::{
+    db::{
+        book::{BookSubscription, BookType},
+        chapter::Chapter,
+        event::Event,
+    },
+    templates::{add_event, authenticated},
+};
+
+pub fn markup(
+    username: &str,
+    book_subscription: &BookSubscription,
+    chapter: &Chapter,
+    events: &[Event],
+    teams: &HashMap<i32, (String, Option<String>)>,
+    pick_counts: &HashMap<i32, i64>,
+) -> maud::Markup {
+    let total_picks: i64 = pick_counts.values().sum();
+
+    authenticated(
+        username,
+        Some(&format!("{} - Edit", chapter.title)),
+        None,
+        Some(maud::html! {
+            script src="/public/js/my-enc.js" {}
+            script src="/public/js/json-enc.js" {}
+            (crate::templates::alertify())
+        }),
+        Some(maud::html! {
+            p {
+                a href="/" class="text-blue-400 hover:underline" {"Home"} " > "
+                a href="../../../.." class="text-blue-400 hover:underline" { (book_subscription.name) } " > "
+                a href="../.." class="text-blue-400 hover:underline" { (chapter.title) } " > "
+                a href=".." class="text-blue-400 hover:underline" { "Admin" } " > "
+                a { "Edit" }
+            }
+        }),
+        Some(maud::html! {
+            input type="text" name="chapter-name" value=(chapter.title) class="border border-green-300" placeholder="Chapter Name" form="submit-events" required;
+            details class="my-2" {
+                summary { "Tiebreaker (optional)" }
+                input type="text" name="tiebreaker" value=[chapter.tiebreaker.as_deref()] class="border border-green-300" placeholder="e.g. Monday night total points" form="submit-events";
+            }
+            details class="my-2" open[chapter.notes.is_some()] {
+                summary { "Notes (optional)" }
+                textarea name="notes" rows="4" placeholder="Markdown shown above the picks" form="submit-events" class="w-full max-w-lg border border-green-300" { (chapter.notes.as_deref().unwrap_or_default()) }
+            }
+
+            @if total_picks > 0 {
+                div class="p-2 m-3 bg-white border border-orange-600 rounded-lg shadow-md w-fit" {
+                    p { (total_picks) " pick(s) have already been made in this chapter" }
+                    p class="text-sm" { "Removing an event deletes its picks. Changing an event clears its answers and regrades the chapter" }
+                    label {
+                        "Picks on changed events: "
+                        select name="pick-policy" form="submit-events" class="m-1 border border-green-300" {
+                            option value="keep" { "Keep them" }
+                            option value="reset" { "Reset them" }
+                        }
+                    }
+                    p class="text-xs" { "Spread group picks are always reset when games are added, removed or the wager mode changes. Picks on a removed team or option are also reset" }
+                }
+            }
+
+            div id="event-spaces" {
+                @for event in events {
+                    (add_event::existing(event, teams, pick_counts.get(&event.id).copied().unwrap_or_default()))
+                }
+            }
+
+            @if book_subscription.kind != BookType::Survivor {
+                button hx-get="add?type=spread-group" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Spread Group"
+                }
+                button hx-get="add?type=user-input" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add User Input"
+                }
+                button hx-get="add?type=moneyline" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Moneyline"
+                }
+                button hx-get="add?type=over-under" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Over/Under"
+                }
+                button hx-get="add?type=multiple-choice" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Multiple Choice"
+                }
+                button hx-get="add?type=numeric" hx-target="#event-spaces" hx-swap="beforeend" class="px-2 py-2 mx-1 mt-1 font-bold text-white bg-orange-600 rounded hover:bg-orange-700" {
+                    "Add Numeric Prediction"
+                }
+            }
+
+            form id="submit-events" hx-post="." hx-ext="my-enc" hx-confirm=[(total_picks > 0).then_some("Save changes to a chapter that already has picks?")] {
+                button type="submit" class="p-2 mt-3 mb-10 text-black bg-white border border-green-500 hover:border-black hover:bg-green-500 hover:text-white rounded-2xl" {
+                    "Save Changes"
+                }
+            }
+            div {}
+        }),
+        None,
+    )
+}
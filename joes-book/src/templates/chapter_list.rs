@@ -2,23 +2,44 @@ use std::iter::Peekable;
 
 use crate::db::chapter::Chapter;
 
-pub fn markup<'a, I>(book_id: i32, mut chapters: Peekable<I>) -> maud::Markup
+pub fn markup<'a, I>(book_id: i32, chapters: Peekable<I>) -> maud::Markup
 where
     I: Iterator<Item = &'a Chapter>,
 {
+    // Chapters sharing a section are listed together, in the order each section first appears
+    let mut sections: Vec<(Option<&str>, Vec<&Chapter>)> = Vec::new();
+    for chapter in chapters {
+        let section = chapter.section.as_deref();
+        match sections
+            .iter_mut()
+            .find(|(existing, _)| *existing == section)
+        {
+            Some((_, chapters)) => chapters.push(chapter),
+            None => sections.push((section, vec![chapter])),
+        }
+    }
+
     maud::html! {
         div class="flex flex-col items-center justify-center" {
             ul class="items-center self-center justify-center" {
-                @if chapters.peek().is_none() {
+                @if sections.is_empty() {
                     li class="p-3 h-30 w-60" {
                         p { "No Chapters Yet!" }
                     }
                 }
-                @for chapter in chapters {
-                    li {
-                        a href={"/book/"(book_id)"/chapter/"(chapter.chapter_id)"/"} class="object-fill" {
-                            div class="justify-center p-3 m-3 bg-white rounded-lg shadow-lg h-30 w-60" {
-                                (chapter.title)
+                @for (section, chapters) in sections {
+                    @if let Some(section) = section {
+                        li { h3 class="mt-3 text-lg font-semibold" { (section) } }
+                    }
+                    @for chapter in chapters {
+                        li {
+                            a href={"/book/"(book_id)"/chapter/"(chapter.chapter_id)"/"} class="object-fill" {
+                                div class="justify-center p-3 m-3 bg-white rounded-lg shadow-lg h-30 w-60" {
+                                    @if let Some(week) = chapter.week {
+                                        span class="text-sm text-gray-500" { "Week " (week) ": " }
+                                    }
+                                    (chapter.title)
+                                }
                             }
                         }
                     }
@@ -4,20 +4,38 @@ use itertools::izip;
 use maud::{html, Markup};
 
 use crate::db::{
+    book::{BookSubscription, BookType},
     chapter::Chapter,
     event::{EventContent, Pick, UserPick},
-    spread::Spread,
+    moneyline::Moneyline,
+    multiple_choice::MultipleChoice,
+    numeric::{Numeric, NumericScoring},
+    over_under::OverUnder,
+    spread::{SpreadGroup, WagerMode},
+    survivor::Survivor,
     user_input::UserInput,
 };
 
 use super::authenticated;
 
+/// Everything the current user can still change in an open chapter
+pub struct PickForm {
+    pub events: Vec<UserPick>,
+    pub tiebreaker: Option<f64>,
+    /// Survivor teams used in other chapters, mapped to the title of the chapter they were used in
+    pub used_teams: HashMap<i32, String>,
+}
+
 pub fn markup(
     username: &str,
-    book_name: &str,
+    book_subscription: &BookSubscription,
     chapter: &Chapter,
-    user_picks: Vec<UserPick>,
-    is_admin: bool,
+    PickForm {
+        events: user_picks,
+        tiebreaker,
+        used_teams,
+    }: PickForm,
+    revealed: Option<Markup>,
     relevent_teams: HashMap<i32, (String, Option<String>)>,
 ) -> Markup {
     authenticated(
@@ -26,17 +44,18 @@ pub fn markup(
         None,
         Some(html! {
             script src="/public/js/my-enc.js" {}
+            link rel="stylesheet" href="/public/styles/chapter-table.css";
             (crate::templates::alertify())
         }),
         Some(maud::html! {
             p {
                 a href="/" class="text-blue-400 hover:underline" {"Home"} " > "
-                a href="../.." class="text-blue-400 hover:underline" { (book_name) } " > "
+                a href="../.." class="text-blue-400 hover:underline" { (book_subscription.name) } " > "
                 a {(chapter.title)}
             }
         }),
         Some(html! {
-            @if is_admin {
+            @if book_subscription.role.is_admin() {
                 div class="flex justify-center" {
                     fieldset class="w-1/2 border border-orange-600 max-w-60" {
                         legend class="ml-3" { "Admin Section" }
@@ -48,31 +67,62 @@ pub fn markup(
                     }
                 }
             }
-            form id="submit-picks" hx-post="." hx-ext="my-enc" {
-                @if user_picks.is_empty() {
-                    p { "No Events in this Chapter" }
+            @if book_subscription.kind == BookType::Survivor && book_subscription.eliminated_in.is_some() {
+                p class="font-semibold text-red-500" { "You have been eliminated from this pool" }
+            }
+            @if let Some(notes) = &chapter.notes {
+                div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md" {
+                    (crate::templates::markdown(notes))
                 }
-                @for (i, (event, pick)) in user_picks.into_iter().enumerate() {
-                    fieldset name="events" me-insert="array" class="flex items-center justify-center" {
-                        input type="hidden" name="event-id" value=(event.id);
-                        @match event.contents.0 {
-                            EventContent::SpreadGroup(spreads) => (spread_group(spreads, pick, i, &relevent_teams)),
-                            EventContent::UserInput(input) => (user_input(input, pick))
+            }
+            @if user_picks.is_empty() && revealed.is_none() {
+                p { "No Events in this Chapter" }
+            }
+            @if !user_picks.is_empty() {
+                form id="submit-picks" hx-post="." hx-ext="my-enc" {
+                    @for (i, (event, pick)) in user_picks.into_iter().enumerate() {
+                        fieldset name="events" me-insert="array" class="flex items-center justify-center" {
+                            input type="hidden" name="event-id" value=(event.id);
+                            @match event.contents.0 {
+                                EventContent::SpreadGroup(group) => (spread_group(group, pick, i, &relevent_teams)),
+                                EventContent::UserInput(input) => (user_input(input, pick)),
+                                EventContent::Moneyline(moneyline) => (moneyline_pick(moneyline, pick, i, &relevent_teams)),
+                                EventContent::OverUnder(over_under) => (over_under_pick(over_under, pick, i, &relevent_teams)),
+                                EventContent::MultipleChoice(multiple_choice) => (multiple_choice_pick(multiple_choice, pick, i)),
+                                EventContent::Numeric(numeric) => (numeric_pick(numeric, pick)),
+                                EventContent::Survivor(survivor) => (survivor_pick(survivor, pick, i, &used_teams, &relevent_teams))
+                            }
                         }
                     }
-                }
-                button type="submit" class="px-2 py-2 mt-1 mb-10 font-bold text-white bg-green-600 rounded hover:bg-green-700" {
-                    "Submit"
+                    @if let Some(question) = &chapter.tiebreaker {
+                        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+                            h3 class="text-lg font-semibold" { "Tiebreaker" }
+                            h4 { (question) }
+                            input type="number" name="tiebreaker" step="any" placeholder="Your Guess" value=[tiebreaker] required class="block p-1 ml-1 mr-1 text-sm text-center text-gray-900 border border-green-300 rounded-lg";
+                        }
+                    }
+                    button type="submit" class="px-2 py-2 mt-1 mb-10 font-bold text-white bg-green-600 rounded hover:bg-green-700" {
+                        "Submit"
+                    }
                 }
             }
             div {}
+            @if let Some(revealed) = revealed {
+                h3 class="text-lg font-semibold" { "Closed Events" }
+                div class="flex justify-center overflow-scroll border border-black" {
+                    (revealed)
+                }
+            }
         }),
         None,
     )
 }
 
 fn spread_group(
-    spreads: Vec<Spread>,
+    SpreadGroup {
+        spreads,
+        wager_mode,
+    }: SpreadGroup,
     picks: Option<Pick>,
     index: usize,
     relevent_teams: &HashMap<i32, (String, Option<String>)>,
@@ -90,17 +140,41 @@ fn spread_group(
     };
 
     let num_spreads = spreads.len();
+    let now = time::OffsetDateTime::now_utc();
 
     html! {
         div class="m-3 bg-white border border-gray-300 rounded-lg shadow-md" {
             p class="text-lg font-semibold" { "Spreads" }
+            @match wager_mode {
+                WagerMode::Confidence => p class="text-sm" { "Rank each game with a unique wager from 1 to " (num_spreads) },
+                WagerMode::Flat => p class="text-sm" { "Each game is worth 1 point" },
+                WagerMode::Bankroll { total } => p class="text-sm" { "Split up to " (total) " points across the games" },
+            }
             input type="hidden" name="type" value="spread-group";
             @for (i, (spread, choice, wager)) in izip!(spreads, choices, wagers).enumerate() {
+                @let locked = spread.has_started(now);
+                @let picked = matches!(&choice, serde_json::Value::String(s) if s == "home" || s == "away");
+                @let missed = locked && !picked;
                 fieldset name="spreads" me-insert="array" {
+                    @if let Some(start_time) = spread.start_time {
+                        p class="pt-2 text-sm" {
+                            @if locked {
+                                span class="font-semibold text-red-500" { "Locked" } " - "
+                            }
+                            "Kickoff: " (crate::templates::local_time(start_time))
+                        }
+                    }
+                    @if let Some(notes) = &spread.notes {
+                        div class="text-sm" { (crate::templates::markdown(notes)) }
+                    }
+                    @if missed {
+                        input type="hidden" name=(format!("selection[{}-{}]", index, i)) value="unpicked";
+                        input type="hidden" name=(format!("num-points[{}-{}]", index, i)) value="0";
+                    }
                     div class="grid grid-flow-col grid-cols-2 gap-4 p-2" {
                         div class="col-span-1" {
-                            input type="radio" name=(format!("selection[{}-{}]", index, i)) class="absolute opacity-0 peer" value="home" id=(format!("{}-{}-home", index, i)) required checked[matches!(&choice, serde_json::Value::String(s) if s == "home")];
-                            label for=(format!("{}-{}-home", index, i)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                            input type="radio" name=(format!("selection[{}-{}]", index, i)) class="absolute opacity-0 peer" value="home" id=(format!("{}-{}-home", index, i)) required disabled[locked] checked[matches!(&choice, serde_json::Value::String(s) if s == "home")];
+                            label for=(format!("{}-{}-home", index, i)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100 peer-disabled:cursor-not-allowed peer-disabled:opacity-75" {
                                 div {
                                     h3 class="font-semibold" { "Home" }
                                     img src=(relevent_teams[&spread.home_id].1.to_owned().unwrap_or_default()) width="150" height="150" alt="Home Team Logo";
@@ -110,8 +184,8 @@ fn spread_group(
                         }
 
                         div class="col-span-1" {
-                            input type="radio" name=(format!("selection[{}-{}]", index, i)) class="absolute opacity-0 peer" value="away" id=(format!("{}-{}-away", index, i)) required checked[matches!(&choice, serde_json::Value::String(s) if s == "away")];
-                            label for=(format!("{}-{}-away", index, i)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                            input type="radio" name=(format!("selection[{}-{}]", index, i)) class="absolute opacity-0 peer" value="away" id=(format!("{}-{}-away", index, i)) required disabled[locked] checked[matches!(&choice, serde_json::Value::String(s) if s == "away")];
+                            label for=(format!("{}-{}-away", index, i)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100 peer-disabled:cursor-not-allowed peer-disabled:opacity-75" {
                                 div {
                                     h3 class="font-semibold" { "Away" }
                                     img src=(relevent_teams[&spread.away_id].1.to_owned().unwrap_or_default()) width="150" height="150" alt="Away Team Logo";
@@ -121,17 +195,29 @@ fn spread_group(
                         }
                     }
 
-                    ul {
-                        @for j in 1..=num_spreads {
-                            li class="inline-flex items-center p-1" {
-                                input type="radio" value=(j) name=(format!("num-points[{}-{}]", index, i)) id=(format!("{}-{}-{}", index, i, j)) class="absolute opacity-0 peer" required checked[matches!(&wager, serde_json::Value::Number(n) if n == &serde_json::Number::from(j))];
-                                label for=(format!("{}-{}-{}", index, i, j)) class="flex items-center justify-center w-5 h-5 p-5 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
-                                    p class="text-xl font-bold" {
-                                        (j)
+                    @match wager_mode {
+                        _ if missed => p class="text-sm text-red-500" { "Missed - no points wagered" },
+                        WagerMode::Confidence => {
+                            ul {
+                                @for j in 1..=num_spreads {
+                                    li class="inline-flex items-center p-1" {
+                                        input type="radio" value=(j) name=(format!("num-points[{}-{}]", index, i)) id=(format!("{}-{}-{}", index, i, j)) class="absolute opacity-0 peer" required disabled[locked && picked] checked[matches!(&wager, serde_json::Value::Number(n) if n == &serde_json::Number::from(j))];
+                                        label for=(format!("{}-{}-{}", index, i, j)) class="flex items-center justify-center w-5 h-5 p-5 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100 peer-disabled:cursor-not-allowed" {
+                                            p class="text-xl font-bold" {
+                                                (j)
+                                            }
+                                        }
                                     }
                                 }
                             }
                         }
+                        WagerMode::Flat => {}
+                        WagerMode::Bankroll { total } => {
+                            div class="p-1" {
+                                label for=(format!("{}-{}-points", index, i)) class="mr-1" { "Wager" }
+                                input type="number" name=(format!("num-points[{}-{}]", index, i)) id=(format!("{}-{}-points", index, i)) min="0" max=(total) value=(wager.as_i64().unwrap_or_default()) required readonly[locked] class="w-20 p-1 text-center border border-green-300 rounded-lg";
+                            }
+                        }
                     }
                 }
             }
@@ -165,3 +251,167 @@ fn user_input(input: UserInput, pick: Option<Pick>) -> Markup {
         }
     }
 }
+
+fn moneyline_pick(
+    moneyline: Moneyline,
+    pick: Option<Pick>,
+    index: usize,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> Markup {
+    let choice = pick.and_then(|p| p.choice.as_str().map(String::from));
+
+    html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md" {
+            p class="text-lg font-semibold" { "Moneyline" }
+            input type="hidden" name="type" value="moneyline";
+            div class="grid grid-flow-col grid-cols-2 gap-4 p-2" {
+                @for (side, team_id) in [("home", moneyline.home_id), ("away", moneyline.away_id)] {
+                    div class="col-span-1" {
+                        input type="radio" name=(format!("selection[{}]", index)) class="absolute opacity-0 peer" value=(side) id=(format!("{}-{}", index, side)) required checked[choice.as_deref() == Some(side)];
+                        label for=(format!("{}-{}", index, side)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                            div {
+                                h3 class="font-semibold" { @if side == "home" { "Home" } @else { "Away" } }
+                                img src=(relevent_teams[&team_id].1.to_owned().unwrap_or_default()) width="150" height="150" alt="Team Logo";
+                                p { (relevent_teams[&team_id].0) }
+                            }
+                        }
+                    }
+                }
+            }
+            @if moneyline.points == 1 {
+                p { "(" (moneyline.points) " Point)" }
+            } @else {
+                p { "(" (moneyline.points) " Points)" }
+            }
+        }
+    }
+}
+
+fn over_under_pick(
+    over_under: OverUnder,
+    pick: Option<Pick>,
+    index: usize,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> Markup {
+    let choice = pick.and_then(|p| p.choice.as_str().map(String::from));
+
+    html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md" {
+            p class="text-lg font-semibold" { "Over/Under" }
+            input type="hidden" name="type" value="over-under";
+            p { (relevent_teams[&over_under.away_id].0) " at " (relevent_teams[&over_under.home_id].0) }
+            div class="grid grid-flow-col grid-cols-2 gap-4 p-2" {
+                @for (side, label) in [("over", "Over"), ("under", "Under")] {
+                    div class="col-span-1" {
+                        input type="radio" name=(format!("selection[{}]", index)) class="absolute opacity-0 peer" value=(side) id=(format!("{}-{}", index, side)) required checked[choice.as_deref() == Some(side)];
+                        label for=(format!("{}-{}", index, side)) class="inline-grid w-full p-5 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                            p class="font-semibold" { (label) " " (over_under.total) }
+                        }
+                    }
+                }
+            }
+            @if over_under.points == 1 {
+                p { "(" (over_under.points) " Point)" }
+            } @else {
+                p { "(" (over_under.points) " Points)" }
+            }
+        }
+    }
+}
+
+fn multiple_choice_pick(
+    multiple_choice: MultipleChoice,
+    pick: Option<Pick>,
+    index: usize,
+) -> Markup {
+    let choice = pick.and_then(|p| p.choice.as_str().map(String::from));
+
+    html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="text-lg font-semibold" { (multiple_choice.title) }
+            input type="hidden" name="type" value="multiple-choice";
+            @if let Some(description) = multiple_choice.description {
+                h4 { (description) }
+            }
+            @for (i, option) in multiple_choice.options.iter().enumerate() {
+                div {
+                    input type="radio" name=(format!("selection[{}]", index)) class="absolute opacity-0 peer" value=(option) id=(format!("{}-option-{}", index, i)) required checked[choice.as_ref() == Some(option)];
+                    label for=(format!("{}-option-{}", index, i)) class="inline-grid w-full p-2 mb-1 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100" {
+                        p class="font-semibold" { (option) }
+                    }
+                }
+            }
+            @if multiple_choice.points == 1 {
+                p { "(" (multiple_choice.points) " Point)" }
+            } @else {
+                p { "(" (multiple_choice.points) " Points)" }
+            }
+        }
+    }
+}
+
+fn numeric_pick(numeric: Numeric, pick: Option<Pick>) -> Markup {
+    let value = pick.and_then(|p| p.choice.as_f64());
+
+    html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md w-fit" {
+            h3 class="text-lg font-semibold" { (numeric.title) }
+            input type="hidden" name="type" value="numeric";
+            @if let Some(description) = numeric.description {
+                h4 { (description) }
+            }
+
+            label class="block mb-2 text-sm font-medium" {
+                input type="number" name="guess" step="any" placeholder="Make Guess" value=[value] required class="block p-1 ml-1 mr-1 text-sm text-center text-gray-900 border border-green-300 rounded-lg focus:ring-blue-500 focus:border-blue-500";
+            }
+
+            @match numeric.scoring {
+                NumericScoring::Closest => p class="text-sm" { "Closest guess wins" },
+                NumericScoring::Distance => p class="text-sm" { "Lose a point for each unit off" },
+            }
+            @if numeric.points == 1 {
+                p { "(" (numeric.points) " Point)" }
+            } @else {
+                p { "(" (numeric.points) " Points)" }
+            }
+        }
+    }
+}
+
+fn survivor_pick(
+    survivor: Survivor,
+    pick: Option<Pick>,
+    index: usize,
+    used_teams: &HashMap<i32, String>,
+    relevent_teams: &HashMap<i32, (String, Option<String>)>,
+) -> Markup {
+    let choice = pick.and_then(|p| p.choice.as_i64());
+
+    html! {
+        div class="p-2 m-3 bg-white border border-gray-300 rounded-lg shadow-md" {
+            p class="text-lg font-semibold" { "Survivor" }
+            p class="text-sm" { "Pick one team to win. Each team can only be used once." }
+            input type="hidden" name="type" value="survivor";
+            @for game in survivor.games {
+                div class="grid grid-flow-col grid-cols-2 gap-4 p-2" {
+                    @for team_id in [game.home_id, game.away_id] {
+                        @let used_in = used_teams.get(&team_id);
+                        div class="col-span-1" {
+                            input type="radio" name=(format!("selection[{}]", index)) class="absolute opacity-0 peer" value=(team_id) id=(format!("{}-{}", index, team_id)) required disabled[used_in.is_some()] checked[choice == Some(team_id.into())];
+                            label for=(format!("{}-{}", index, team_id)) class="inline-grid w-full p-5 pt-0 pb-0 border border-black rounded-lg cursor-pointer hover:border-green-700 peer-checked:bg-green-500 peer-checked:border-green-600 hover:bg-green-100 peer-disabled:cursor-not-allowed peer-disabled:opacity-50" {
+                                div {
+                                    h3 class="font-semibold" { @if team_id == game.home_id { "Home" } @else { "Away" } }
+                                    img src=(relevent_teams[&team_id].1.to_owned().unwrap_or_default()) width="150" height="150" alt="Team Logo";
+                                    p { (relevent_teams[&team_id].0) }
+                                    @if let Some(chapter_title) = used_in {
+                                        p class="text-sm text-red-500" { "Used in " (chapter_title) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
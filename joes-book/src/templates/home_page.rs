@@ -1,13 +1,18 @@
-use crate::{db::book::BookSubscription, templates::book_list};
+use crate::{
+    db::book::{BookSubscription, MAX_BOOK_NAME_LEN},
+    templates::book_list,
+};
 
 use super::authenticated;
 
 pub fn markup(username: &str, is_admin: bool, books: Vec<BookSubscription>) -> maud::Markup {
+    let (archived, books): (Vec<_>, Vec<_>) = books.into_iter().partition(|book| book.archived);
+
     authenticated(
         username,
         Some("Home"),
         None,
-        None,
+        Some(crate::templates::alertify()),
         Some(maud::html! {
              p {
                 a {"Home"}
@@ -18,13 +23,34 @@ pub fn markup(username: &str, is_admin: bool, books: Vec<BookSubscription>) -> m
                 form action="/book/create" method="post" {
                     label {
                         p {"Create New Book"}
-                        input type="text" placeholder="Book Name" maxlength="32" name="book-name";
+                        input type="text" placeholder="Book Name" required maxlength=(MAX_BOOK_NAME_LEN) name="book-name";
+                    }
+                    select name="book-type" {
+                        option value="standard" { "Standard" }
+                        option value="survivor" { "Survivor" }
                     }
                     button type="submit" class="button" { "Create" }
                 }
             }
             h3 class="h-3" { b { "Your Books"}}
             (book_list::markup(books))
+            @if !archived.is_empty() {
+                details class="my-2" {
+                    summary { b { "Archived" } }
+                    (book_list::markup(archived))
+                }
+            }
+            h3 class="h-3" { b { "Find a Book" } }
+            input
+                name="name"
+                hx-get="/book/search"
+                hx-trigger="input changed delay:200ms, search"
+                hx-target="next ul"
+                type="search"
+                autocomplete="off"
+                placeholder="Book name"
+                class="border border-green-300";
+            ul {}
         }),
         None,
     )
@@ -1,9 +1,11 @@
-use maud::{html, Markup, DOCTYPE};
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use time::format_description::well_known::Rfc3339;
 
 pub mod add_event;
 pub mod book_list;
 pub mod chapter_admin;
 pub mod chapter_create;
+pub mod chapter_edit;
 pub mod chapter_list;
 pub mod chapter_open;
 pub mod home_page;
@@ -30,6 +32,15 @@ pub fn base(
                 link rel="manifest" href="/public/manifest.json";
                 link rel="stylesheet" id="tailwind" href="/public/styles/bookie.css";
                 script src="/public/js/htmx.js" {}
+                script {
+                    "htmx.onLoad(function (elt) {
+                        elt.querySelectorAll('time[datetime]').forEach(function (time) {
+                            time.textContent = new Date(time.getAttribute('datetime')).toLocaleString([], {
+                                weekday: 'short', month: 'short', day: 'numeric', hour: 'numeric', minute: '2-digit'
+                            });
+                        });
+                    });"
+                }
 
                 @if let Some(graph) = open_graph {
                     (graph)
@@ -111,3 +122,49 @@ pub fn alertify() -> maud::Markup {
         }
     }
 }
+
+/// Renders admin-written markdown. Raw HTML is sanitized so notes can't inject scripts
+pub fn markdown(text: &str) -> Markup {
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(text));
+
+    html! {
+        div class="markdown" { (PreEscaped(ammonia::clean(&html))) }
+    }
+}
+
+/// Renders a UTC timestamp, which is swapped to the viewer's local time on load
+pub fn local_time(time: time::OffsetDateTime) -> Markup {
+    let time = time.to_offset(time::UtcOffset::UTC);
+    let fallback = time::macros::format_description!(
+        "[weekday repr:short] [month repr:short] [day padding:none], [hour repr:12 padding:none]:[minute] [period] UTC"
+    );
+
+    html! {
+        time datetime=(time.format(&Rfc3339).unwrap_or_default()) { (time.format(fallback).unwrap_or_default()) }
+    }
+}
+
+/// A `datetime-local` picker that submits its value as an RFC 3339 UTC string under `name`
+pub fn utc_datetime_input(name: &str, form: Option<&str>) -> Markup {
+    utc_datetime_input_value(name, form, None)
+}
+
+/// [`utc_datetime_input`] that keeps submitting `value` until a new time is picked
+pub fn utc_datetime_input_value(
+    name: &str,
+    form: Option<&str>,
+    value: Option<time::OffsetDateTime>,
+) -> Markup {
+    let value = value
+        .and_then(|value| value.to_offset(time::UtcOffset::UTC).format(&Rfc3339).ok())
+        .unwrap_or_default();
+
+    html! {
+        input
+            type="datetime-local"
+            onchange="this.nextElementSibling.value = this.value ? new Date(this.value).toISOString() : ''"
+            class="m-1 border border-green-300";
+        input type="hidden" name=(name) form=[form] value=(value);
+    }
+}
@@ -0,0 +1,31 @@
+// Lets the children of any element with a `drag-order` attribute be reordered by dragging.
+// A `reordered` event is dispatched on the container after each drop
+document.addEventListener('dragstart', function (evt) {
+	const item = evt.target.closest && evt.target.closest('[drag-order] > *');
+	if (item) {
+		item.classList.add('opacity-50');
+		window.dragOrderItem = item;
+	}
+});
+
+document.addEventListener('dragover', function (evt) {
+	const dragged = window.dragOrderItem;
+	const target = evt.target.closest && evt.target.closest('[drag-order] > *');
+	if (!dragged || !target || target === dragged || target.parentElement !== dragged.parentElement) {
+		return;
+	}
+
+	evt.preventDefault();
+	const bounds = target.getBoundingClientRect();
+	const after = evt.clientY > bounds.top + bounds.height / 2;
+	target.parentElement.insertBefore(dragged, after ? target.nextSibling : target);
+});
+
+document.addEventListener('dragend', function () {
+	const dragged = window.dragOrderItem;
+	if (dragged) {
+		dragged.classList.remove('opacity-50');
+		dragged.parentElement.dispatchEvent(new Event('reordered', { bubbles: true }));
+		window.dragOrderItem = null;
+	}
+});